@@ -8,11 +8,17 @@ fn main() -> Result<(), anyhow::Error> {
         "abi/factory_contract.abi.json",
         "abi/lendergroup_contract.abi.json",
         "abi/collateral_manager.abi.json",
+        "abi/erc20.abi.json",
+        "abi/collateral_escrow.abi.json",
+        "abi/tellerv2.abi.json",
     ];
     let file_output_names = [
         "src/abi/factory_contract.rs",
         "src/abi/lendergroup_contract.rs",
         "src/abi/collateral_manager.rs",
+        "src/abi/erc20.rs",
+        "src/abi/collateral_escrow.rs",
+        "src/abi/tellerv2.rs",
     ];
 
     let mut i = 0;
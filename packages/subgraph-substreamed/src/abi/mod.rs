@@ -1,4 +1,7 @@
 
 pub mod factory_contract;
 pub mod lendergroup_contract;
-pub mod collateral_manager;
\ No newline at end of file
+pub mod collateral_manager;
+pub mod erc20;
+pub mod collateral_escrow;
+pub mod tellerv2;
@@ -15,6 +15,8 @@ use substreams_entity_change::pb::entity::EntityChanges;
 use substreams_entity_change::tables::Tables as EntityChangesTables;
 use substreams_ethereum::pb::eth::v2 as eth;
 use substreams_ethereum::Event;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
 use std::collections::HashSet;
 
 
@@ -28,9 +30,79 @@ substreams_ethereum::init!();
 
 const FACTORY_TRACKED_CONTRACT: [u8; 20] = hex!("44Ce8fA66d6eDF0c5c668b818A922E772C72568B");
 const COLLATERAL_MANAGER_TRACKED_CONTRACT: [u8;20] = hex!("76888a882a4fF57455B5e74B791DD19DF3ba51Bb");
+const ZERO_ADDRESS: [u8;20] = hex!("0000000000000000000000000000000000000000");
+const COLLATERAL_TYPE_ERC20: &str = "0"; // CollateralType enum from the CollateralManager, as it appears in store keys 
+
+// erc20 collateral is a single fungible balance, nft collateral is tracked per token id 
+fn is_fungible_collateral(collateral_type: &str) -> bool {
+    collateral_type == COLLATERAL_TYPE_ERC20
+}
+
+// group_user_metric:{group_pool_address}:{user_address}:{metric} 
+fn parse_group_user_metric_key(key: &str) -> Option<(&str, &str, &str)> {
+    let mut segments = key.split(':');
+
+    match (segments.next()?, segments.next()?, segments.next()?, segments.next()?, segments.next()) {
+        ("group_user_metric", group_pool_address, user_address, metric, None) => Some((group_pool_address, user_address, metric)),
+        _ => None,
+    }
+}
+
+struct PoolCollateralAssetKey<'a> {
+    group_pool_address: &'a str,
+    collateral_address: &'a str,
+    collateral_type: &'a str,
+    token_id: &'a str,
+    metric: &'a str,
+}
+
+// pool_collateral_asset:{group_pool_address}:{collateral_address}:{collateral_type}:{token_id}:{metric} 
+fn parse_pool_collateral_asset_key(key: &str) -> Option<PoolCollateralAssetKey<'_>> {
+    let mut segments = key.split(':');
+
+    if segments.next()? != "pool_collateral_asset" {
+        return None;
+    }
+
+    let parsed_key = PoolCollateralAssetKey {
+        group_pool_address: segments.next()?,
+        collateral_address: segments.next()?,
+        collateral_type: segments.next()?,
+        token_id: segments.next()?,
+        metric: segments.next()?,
+    };
+
+    match segments.next() {
+        None => Some(parsed_key),
+        Some(_) => None,
+    }
+}
+
+// a position is worth  shares * shares_exchange_rate / expansion factor  in principal tokens 
+fn shares_position_value(shares_tokens_balance: &BigInt, shares_exchange_rate: &BigInt, exchange_rate_expansion_factor: &BigInt) -> BigInt {
+    if exchange_rate_expansion_factor == &BigInt::zero() {
+        return BigInt::zero();
+    }
+
+    shares_tokens_balance.clone() * shares_exchange_rate.clone() / exchange_rate_expansion_factor.clone()
+}
+
+// what the lender holds plus what they took out, minus what they put in. 
+// shares moved between wallets carry no cost basis, so interest is only known for lenders who minted and burned their own shares 
+fn lender_interest_earned(
+    shares_balance_value: &BigInt,
+    total_principal_tokens_withdrawn: &BigInt,
+    total_principal_tokens_committed: &BigInt,
+    has_secondary_share_transfers: bool,
+) -> Option<BigInt> {
+    match has_secondary_share_transfers {
+        true => None,
+        false => Some(shares_balance_value.clone() + total_principal_tokens_withdrawn.clone() - total_principal_tokens_committed.clone()),
+    }
+}
 
 fn map_factory_events(blk: &eth::Block, events: &mut contract::Events) {
-    /*events.factory_admin_changeds.append(&mut blk
+    events.factory_admin_changeds.append(&mut blk
         .receipts()
         .flat_map(|view| {
             view.receipt.logs.iter()
@@ -93,9 +165,50 @@ fn map_factory_events(blk: &eth::Block, events: &mut contract::Events) {
                 })
         })
         .collect());
-        
-        
-        */
+
+    events.factory_ownership_transferreds.append(&mut blk
+        .receipts()
+        .flat_map(|view| {
+            view.receipt.logs.iter()
+                .filter(|log| log.address == FACTORY_TRACKED_CONTRACT)
+                .filter_map(|log| {
+                    if let Some(event) = abi::factory_contract::events::OwnershipTransferred::match_and_decode(log) {
+                        return Some(contract::FactoryOwnershipTransferred {
+                            evt_tx_hash: Hex(&view.transaction.hash).to_string(),
+                            evt_index: log.block_index,
+                            evt_block_time: blk.timestamp_seconds(),
+                            evt_block_number: blk.number,
+                            new_owner: event.new_owner,
+                            previous_owner: event.previous_owner,
+                        });
+                    }
+
+                    None
+                })
+        })
+        .collect());
+
+    events.factory_initializeds.append(&mut blk
+        .receipts()
+        .flat_map(|view| {
+            view.receipt.logs.iter()
+                .filter(|log| log.address == FACTORY_TRACKED_CONTRACT)
+                .filter_map(|log| {
+                    if let Some(event) = abi::factory_contract::events::Initialized::match_and_decode(log) {
+                        return Some(contract::FactoryInitialized {
+                            evt_tx_hash: Hex(&view.transaction.hash).to_string(),
+                            evt_index: log.block_index,
+                            evt_block_time: blk.timestamp_seconds(),
+                            evt_block_number: blk.number,
+                            version: event.version.to_u64(),
+                        });
+                    }
+
+                    None
+                })
+        })
+        .collect());
+
     events.factory_deployed_lender_group_contracts.append(&mut blk
         .receipts()
         .flat_map(|view| {
@@ -149,6 +262,118 @@ fn is_declared_dds_address(addr: &Vec<u8>, ordinal: u64, dds_store: &store::Stor
     return false;
 }
 
+/*
+
+    Each pool deploys its own shares token on initialize, so register it here
+    and resolve transfers back to the pool that owns the token.
+
+*/
+#[substreams::handlers::store]
+fn store_pool_shares_tokens(
+    events:  contract::Events, 
+
+    string_set_store: StoreSetString
+) {
+
+    let ord = 0; // FOR NOW - CAN CAUSE ISSUES - GET FROM LOG AND STUFF INTO EVENT    
+
+    events.lendergroup_pool_initializeds.iter().for_each(|evt: &contract::LendergroupPoolInitialized| {
+
+        string_set_store.set(ord, format!("pool_shares_token:{}", Hex(&evt.pool_shares_token).to_string()), &evt.evt_address );
+
+    });
+
+}
+
+
+// every wallet currently holding shares of a pool, dropped again once its shares balance reaches zero 
+#[substreams::handlers::store]
+fn store_pool_share_holders(
+    deltas_lendergroup_user_metrics: Deltas<DeltaBigInt>,
+
+    string_set_store: StoreSetString
+) {
+
+    for user_metric_delta in deltas_lendergroup_user_metrics.deltas.iter() {
+
+        let Some((group_address, user_address, delta_prop_identifier)) = parse_group_user_metric_key(user_metric_delta.get_key()) else {continue};
+
+        if delta_prop_identifier != "shares_tokens_balance" {continue};
+
+        let store_key = format!("pool_share_holder:{}:{}", group_address, user_address);
+
+        if user_metric_delta.new_value == BigInt::zero() {
+            string_set_store.delete_prefix(user_metric_delta.ordinal as i64, &store_key);
+        } else {
+            string_set_store.set(user_metric_delta.ordinal, &store_key, &user_address.to_string());
+        }
+    }
+
+}
+
+
+/*
+    Every wallet that ever came to hold shares of a pool, so graph_out can walk the holders of a pool. 
+    A wallet is appended each time its balance leaves zero; readers drop repeats and wallets missing from store_pool_share_holders. 
+*/
+#[substreams::handlers::store]
+fn store_pool_share_holder_lists(
+    deltas_lendergroup_user_metrics: Deltas<DeltaBigInt>,
+
+    string_append_store: StoreAppend<String>
+) {
+
+    for user_metric_delta in deltas_lendergroup_user_metrics.deltas.iter() {
+
+        let Some((group_address, user_address, delta_prop_identifier)) = parse_group_user_metric_key(user_metric_delta.get_key()) else {continue};
+
+        if delta_prop_identifier != "shares_tokens_balance" {continue};
+
+        if user_metric_delta.old_value != BigInt::zero() || user_metric_delta.new_value == BigInt::zero() {continue};
+
+        string_append_store.append(user_metric_delta.ordinal, format!("pool_share_holder_list:{}", group_address), user_address.to_string());
+    }
+
+}
+
+
+#[substreams::handlers::map]
+fn map_pool_shares_events(
+    blk: eth::Block,
+    store_pool_shares_tokens: StoreGetString,
+) -> Result<contract::Events, substreams::errors::Error> {
+    let mut events = contract::Events::default();
+
+    events.lendergroup_pool_shares_transfers.append(&mut blk
+        .receipts()
+        .flat_map(|view| {
+            view.receipt.logs.iter()
+                .filter_map(|log| {
+                    // decode first so only Transfer logs pay for the store lookup
+                    let event = abi::erc20::events::Transfer::match_and_decode(log)?;
+
+                    let group_pool_address = store_pool_shares_tokens.get_last(format!("pool_shares_token:{}", Hex(&log.address).to_string()))?;
+
+                    Some(contract::LendergroupPoolSharesTransfer {
+                        evt_tx_hash: Hex(&view.transaction.hash).to_string(),
+                        evt_index: log.block_index,
+                        evt_block_time: blk.timestamp_seconds(),
+                        evt_block_number: blk.number,
+                        evt_address: Hex(&log.address).to_string(),
+                        group_pool_address,
+                        from: event.src,
+                        to: event.dst,
+                        amount: event.wad.to_string(),
+                    })
+                })
+        })
+        .collect());
+
+    Ok(events)
+}
+
+
+
 fn map_lendergroup_events(
     blk: &eth::Block,
     dds_store: &store::StoreGetInt64,   //this is used to know abt lendergroup contract address
@@ -351,6 +576,73 @@ fn map_lendergroup_events(
         })
         .collect());
 
+    events.lendergroup_admin_changeds.append(&mut blk
+        .receipts()
+        .flat_map(|view| {
+            view.receipt.logs.iter()
+                .filter(|log| is_declared_dds_address(&log.address, log.ordinal, dds_store))
+                .filter_map(|log| {
+                    if let Some(event) = abi::lendergroup_contract::events::AdminChanged::match_and_decode(log) {
+                        return Some(contract::LendergroupAdminChanged {
+                            evt_tx_hash: Hex(&view.transaction.hash).to_string(),
+                            evt_index: log.block_index,
+                            evt_block_time: blk.timestamp_seconds(),
+                            evt_block_number: blk.number,
+                            evt_address: Hex(&log.address).to_string(),
+                            new_admin: event.new_admin,
+                            previous_admin: event.previous_admin,
+                        });
+                    }
+
+                    None
+                })
+        })
+        .collect());
+
+    events.lendergroup_beacon_upgradeds.append(&mut blk
+        .receipts()
+        .flat_map(|view| {
+            view.receipt.logs.iter()
+                .filter(|log| is_declared_dds_address(&log.address, log.ordinal, dds_store))
+                .filter_map(|log| {
+                    if let Some(event) = abi::lendergroup_contract::events::BeaconUpgraded::match_and_decode(log) {
+                        return Some(contract::LendergroupBeaconUpgraded {
+                            evt_tx_hash: Hex(&view.transaction.hash).to_string(),
+                            evt_index: log.block_index,
+                            evt_block_time: blk.timestamp_seconds(),
+                            evt_block_number: blk.number,
+                            evt_address: Hex(&log.address).to_string(),
+                            beacon: event.beacon,
+                        });
+                    }
+
+                    None
+                })
+        })
+        .collect());
+
+    events.lendergroup_upgradeds.append(&mut blk
+        .receipts()
+        .flat_map(|view| {
+            view.receipt.logs.iter()
+                .filter(|log| is_declared_dds_address(&log.address, log.ordinal, dds_store))
+                .filter_map(|log| {
+                    if let Some(event) = abi::lendergroup_contract::events::Upgraded::match_and_decode(log) {
+                        return Some(contract::LendergroupUpgraded {
+                            evt_tx_hash: Hex(&view.transaction.hash).to_string(),
+                            evt_index: log.block_index,
+                            evt_block_time: blk.timestamp_seconds(),
+                            evt_block_number: blk.number,
+                            evt_address: Hex(&log.address).to_string(),
+                            implementation: event.implementation,
+                        });
+                    }
+
+                    None
+                })
+        })
+        .collect());
+
     events.lendergroup_pool_initializeds.append(&mut blk
         .receipts()
         .flat_map(|view| {
@@ -404,6 +696,30 @@ fn map_lendergroup_events(
         })
         .collect());
 
+    events.lendergroup_pool_shares_prepareds.append(&mut blk
+        .receipts()
+        .flat_map(|view| {
+            view.receipt.logs.iter()
+                .filter(|log| is_declared_dds_address(&log.address, log.ordinal, dds_store))
+                .filter_map(|log| {
+                    if let Some(event) = abi::lendergroup_contract::events::PoolSharesPrepared::match_and_decode(log) {
+                        return Some(contract::LendergroupPoolSharesPrepared {
+                            evt_tx_hash: Hex(&view.transaction.hash).to_string(),
+                            evt_index: log.block_index,
+                            evt_block_time: blk.timestamp_seconds(),
+                            evt_block_number: blk.number,
+                            evt_address: Hex(&log.address).to_string(),
+                            lender: event.lender,
+                            shares_amount: event.shares_amount.to_string(),
+                            prepared_at: event.prepared_at.to_u64(),
+                        });
+                    }
+
+                    None
+                })
+        })
+        .collect());
+
     events.lendergroup_unpauseds.append(&mut blk
         .receipts()
         .flat_map(|view| {
@@ -430,14 +746,14 @@ fn map_lendergroup_events(
 
 fn graph_factory_out(events: &contract::Events, tables: &mut EntityChangesTables) {
     // Loop over all the abis events to create table changes
-   /*events.factory_admin_changeds.iter().for_each(|evt| {
+    events.factory_admin_changeds.iter().for_each(|evt| {
         tables
             .create_row("factory_admin_changed", format!("{}-{}", evt.evt_tx_hash, evt.evt_index))
             .set("evt_tx_hash", evt.evt_tx_hash.clone().into_bytes())
             .set("evt_index", BigInt::from( evt.evt_index)  )
             .set("evt_block_time", BigInt::from ( evt.evt_block_time )) 
             .set("evt_block_number", BigInt::from( evt.evt_block_number) )
-            .set("new_admin",  &evt.new_admin )  //throws an error ??
+            .set("new_admin",  &evt.new_admin )
             .set("previous_admin",  &evt.previous_admin  );
     });
     
@@ -460,7 +776,6 @@ fn graph_factory_out(events: &contract::Events, tables: &mut EntityChangesTables
             .set("evt_block_number", BigInt::from(evt.evt_block_number))
             .set("implementation",  &evt.implementation  );
     });
-    */
      
   
     events.factory_deployed_lender_group_contracts.iter().for_each(|evt| {
@@ -476,6 +791,175 @@ fn graph_factory_out(events: &contract::Events, tables: &mut EntityChangesTables
 }
 
 
+fn create_governance_action_row<'a>(
+    tables: &'a mut EntityChangesTables,
+    evt_tx_hash: &String,
+    evt_index: u32,
+    evt_block_time: u64,
+    evt_block_number: u64,
+    contract_type: &str,
+    contract_address: String,
+    action: &str,
+) -> &'a mut substreams_entity_change::tables::Row {
+    tables
+        .create_row("governance_action", format!("{}-{}", evt_tx_hash, evt_index))
+        .set("evt_tx_hash", evt_tx_hash)
+        .set("evt_index", BigInt::from(evt_index))
+        .set("evt_block_time", BigInt::from(evt_block_time))
+        .set("evt_block_number", BigInt::from(evt_block_number))
+        .set("contract_type", contract_type)
+        .set("contract_address", contract_address)
+        .set("action", action)
+}
+
+// privileged actions on the factory proxy and on every lender group pool, in one table
+fn graph_governance_out(events: &contract::Events, tables: &mut EntityChangesTables) {
+    events.factory_admin_changeds.iter().for_each(|evt| {
+        create_governance_action_row(tables, &evt.evt_tx_hash, evt.evt_index, evt.evt_block_time, evt.evt_block_number,
+            "lender_group_factory", format!("0x{}", Hex(&FACTORY_TRACKED_CONTRACT)), "admin_changed")
+            .set("previous_value", format!("0x{}", Hex(&evt.previous_admin)))
+            .set("new_value", format!("0x{}", Hex(&evt.new_admin)));
+    });
+    events.factory_beacon_upgradeds.iter().for_each(|evt| {
+        create_governance_action_row(tables, &evt.evt_tx_hash, evt.evt_index, evt.evt_block_time, evt.evt_block_number,
+            "lender_group_factory", format!("0x{}", Hex(&FACTORY_TRACKED_CONTRACT)), "beacon_upgraded")
+            .set("new_value", format!("0x{}", Hex(&evt.beacon)));
+    });
+    events.factory_upgradeds.iter().for_each(|evt| {
+        create_governance_action_row(tables, &evt.evt_tx_hash, evt.evt_index, evt.evt_block_time, evt.evt_block_number,
+            "lender_group_factory", format!("0x{}", Hex(&FACTORY_TRACKED_CONTRACT)), "implementation_upgraded")
+            .set("new_value", format!("0x{}", Hex(&evt.implementation)));
+    });
+    events.factory_ownership_transferreds.iter().for_each(|evt| {
+        create_governance_action_row(tables, &evt.evt_tx_hash, evt.evt_index, evt.evt_block_time, evt.evt_block_number,
+            "lender_group_factory", format!("0x{}", Hex(&FACTORY_TRACKED_CONTRACT)), "ownership_transferred")
+            .set("previous_value", format!("0x{}", Hex(&evt.previous_owner)))
+            .set("new_value", format!("0x{}", Hex(&evt.new_owner)));
+    });
+    events.factory_initializeds.iter().for_each(|evt| {
+        create_governance_action_row(tables, &evt.evt_tx_hash, evt.evt_index, evt.evt_block_time, evt.evt_block_number,
+            "lender_group_factory", format!("0x{}", Hex(&FACTORY_TRACKED_CONTRACT)), "initialized")
+            .set("new_value", evt.version.to_string());
+    });
+
+    events.lendergroup_ownership_transferreds.iter().for_each(|evt| {
+        create_governance_action_row(tables, &evt.evt_tx_hash, evt.evt_index, evt.evt_block_time, evt.evt_block_number,
+            "lender_group_pool", format!("0x{}", evt.evt_address), "ownership_transferred")
+            .set("previous_value", format!("0x{}", Hex(&evt.previous_owner)))
+            .set("new_value", format!("0x{}", Hex(&evt.new_owner)));
+    });
+    events.lendergroup_pauseds.iter().for_each(|evt| {
+        create_governance_action_row(tables, &evt.evt_tx_hash, evt.evt_index, evt.evt_block_time, evt.evt_block_number,
+            "lender_group_pool", format!("0x{}", evt.evt_address), "paused")
+            .set("account", format!("0x{}", Hex(&evt.account)));
+    });
+    events.lendergroup_unpauseds.iter().for_each(|evt| {
+        create_governance_action_row(tables, &evt.evt_tx_hash, evt.evt_index, evt.evt_block_time, evt.evt_block_number,
+            "lender_group_pool", format!("0x{}", evt.evt_address), "unpaused")
+            .set("account", format!("0x{}", Hex(&evt.account)));
+    });
+    events.lendergroup_initializeds.iter().for_each(|evt| {
+        create_governance_action_row(tables, &evt.evt_tx_hash, evt.evt_index, evt.evt_block_time, evt.evt_block_number,
+            "lender_group_pool", format!("0x{}", evt.evt_address), "initialized")
+            .set("new_value", evt.version.to_string());
+    });
+    events.lendergroup_admin_changeds.iter().for_each(|evt| {
+        create_governance_action_row(tables, &evt.evt_tx_hash, evt.evt_index, evt.evt_block_time, evt.evt_block_number,
+            "lender_group_pool", format!("0x{}", evt.evt_address), "admin_changed")
+            .set("previous_value", format!("0x{}", Hex(&evt.previous_admin)))
+            .set("new_value", format!("0x{}", Hex(&evt.new_admin)));
+    });
+    events.lendergroup_beacon_upgradeds.iter().for_each(|evt| {
+        create_governance_action_row(tables, &evt.evt_tx_hash, evt.evt_index, evt.evt_block_time, evt.evt_block_number,
+            "lender_group_pool", format!("0x{}", evt.evt_address), "beacon_upgraded")
+            .set("new_value", format!("0x{}", Hex(&evt.beacon)));
+    });
+    events.lendergroup_upgradeds.iter().for_each(|evt| {
+        create_governance_action_row(tables, &evt.evt_tx_hash, evt.evt_index, evt.evt_block_time, evt.evt_block_number,
+            "lender_group_pool", format!("0x{}", evt.evt_address), "implementation_upgraded")
+            .set("new_value", format!("0x{}", Hex(&evt.implementation)));
+    });
+}
+
+fn graph_collateral_out(
+    collateral_events: &collateral_contract::Events,
+    tables: &mut EntityChangesTables,
+    deltas_bid_collateral_amounts: &Deltas<DeltaBigInt>,
+    store_bid_collateral_amounts: &StoreGetBigInt,
+    store_bids_from_pools_data: &StoreGetString,
+) {
+
+    let ord = 0; // FOR NOW - CAN CAUSE ISSUES 
+
+    collateral_events.collateral_manager_collateral_escrow_deployeds.iter().for_each(|evt| {
+        tables
+            .create_row("bid_collateral_escrow", &evt.bid_id)
+            .set("evt_tx_hash", evt.evt_tx_hash.clone().into_bytes())
+            .set("evt_block_time", BigInt::from(evt.evt_block_time))
+            .set("evt_block_number", BigInt::from(evt.evt_block_number))
+            .set("bid_id", BigInt::from_str(&evt.bid_id).unwrap())
+            .set("collateral_escrow", &evt.collateral_escrow );
+    });
+
+    let mut bid_collaterals_detected = HashSet::new();
+
+    for bid_collateral_delta in deltas_bid_collateral_amounts.deltas.iter() {
+
+        let delta_root_identifier = substreams::key::segment_at(bid_collateral_delta.get_key(), 0);
+
+        if delta_root_identifier != "bid_collateral" {continue};
+
+        let bid_id = substreams::key::segment_at(bid_collateral_delta.get_key(), 1);
+        let collateral_address = substreams::key::segment_at(bid_collateral_delta.get_key(), 2);
+        let collateral_type = substreams::key::segment_at(bid_collateral_delta.get_key(), 3);
+        let token_id = substreams::key::segment_at(bid_collateral_delta.get_key(), 4);
+
+        bid_collaterals_detected.insert((bid_id.to_string(), collateral_address.to_string(), collateral_type.to_string(), token_id.to_string()));
+    }
+
+    // write the full row each time so bids first seen mid-lifecycle still satisfy the schema 
+    for (bid_id, collateral_address, collateral_type, token_id) in bid_collaterals_detected.iter() {
+
+        let committed_amount = store_bid_collateral_amounts
+            .get_at(ord, format!("bid_collateral:{}:{}:{}:{}:committed", bid_id, collateral_address, collateral_type, token_id))
+            .unwrap_or(BigInt::zero());
+        let deposited_amount = store_bid_collateral_amounts
+            .get_at(ord, format!("bid_collateral:{}:{}:{}:{}:deposited", bid_id, collateral_address, collateral_type, token_id))
+            .unwrap_or(BigInt::zero());
+        let withdrawn_amount = store_bid_collateral_amounts
+            .get_at(ord, format!("bid_collateral:{}:{}:{}:{}:withdrawn", bid_id, collateral_address, collateral_type, token_id))
+            .unwrap_or(BigInt::zero());
+        let claimed_amount = store_bid_collateral_amounts
+            .get_at(ord, format!("bid_collateral:{}:{}:{}:{}:claimed", bid_id, collateral_address, collateral_type, token_id))
+            .unwrap_or(BigInt::zero());
+
+        let escrow_balance = store_bid_collateral_amounts
+            .get_at(ord, format!("bid_collateral_escrow_balance:{}:{}", bid_id, collateral_address))
+            .unwrap_or(BigInt::zero());
+
+        let net_collateral_escrowed = deposited_amount.clone() - withdrawn_amount.clone();
+
+        let bid_collateral_row = tables
+            .create_row("bid_collateral", format!("{}_{}_{}_{}", bid_id, collateral_address, collateral_type, token_id ))
+            .set("bid_id", BigInt::from_str(bid_id).unwrap())
+            .set("collateral_address", Hex::decode( collateral_address ).unwrap())
+            .set("collateral_type", collateral_type.parse::<i32>().unwrap())
+            .set("token_id", BigInt::from_str(token_id).unwrap_or(BigInt::zero()))
+            .set("committed_amount", &committed_amount )
+            .set("deposited_amount", &deposited_amount )
+            .set("withdrawn_amount", &withdrawn_amount )
+            .set("claimed_amount", &claimed_amount )
+            .set("net_collateral_escrowed", &net_collateral_escrowed )
+            .set("escrow_balance", &escrow_balance );
+
+        if let Some( group_pool_address ) = store_bids_from_pools_data.get_at(ord, format!("bid_originated_from_pool:{}", bid_id)) {
+            bid_collateral_row.set("group_pool_address", Hex::decode( group_pool_address ).unwrap());
+        }
+    }
+
+}
+
+
 //make sure these match schema.graphql ! 
 fn graph_lendergroup_out(
      events: &contract::Events,
@@ -489,10 +973,24 @@ fn graph_lendergroup_out(
      deltas_lendergroup_user_metrics: &Deltas<DeltaBigInt>,
 
      store_collateral_withdrawn_data: &StoreGetBigInt, 
-    // store_get_lendergroup_user_metrics: &StoreGetBigInt, not used 
-  
-    
-    ) {
+     store_get_lendergroup_user_metrics: &StoreGetBigInt, 
+     store_pool_share_holders: &StoreGetString,
+     store_pool_share_holder_lists: &StoreGetArray<String>,
+
+     deltas_pool_collateral_balance: &Deltas<DeltaBigInt>,
+     store_pool_collateral_balance: &StoreGetBigInt,
+     deltas_pool_collateral_token_ids: &Deltas<DeltaString>,
+
+     deltas_withdrawal_requests: &Deltas<DeltaBigInt>,
+
+     pool_shares_events: &contract::Events,
+
+     deltas_group_loan_repayments: &Deltas<DeltaBigInt>,
+
+     tellerv2_events: &contract::Events,
+  
+    
+    ) {
     // Loop over all the abis events to create table changes
     events.lendergroup_borrower_accepted_funds.iter().for_each(|evt| {
         tables
@@ -521,7 +1019,7 @@ fn graph_lendergroup_out(
             */
             
               tables
-            .create_row("group_pool_bid", format!("{}", evt.evt_address )  ) 
+            .create_row("group_pool_bid", &evt.bid_id ) 
            
             .set("group_pool_address", Hex::decode(&evt.evt_address).unwrap() )
             .set("bid_id", BigDecimal::from_str(&evt.bid_id).unwrap() )
@@ -557,6 +1055,31 @@ fn graph_lendergroup_out(
             .set("principal_tokens_withdrawn", BigDecimal::from_str(&evt.principal_tokens_withdrawn).unwrap())
             .set("recipient",  &evt.recipient );
     });
+    events.lendergroup_pool_shares_prepareds.iter().for_each(|evt| {
+        tables
+            .create_row("group_pool_shares_prepared", format!("{}-{}", evt.evt_tx_hash, evt.evt_index))
+            .set("evt_tx_hash", evt.evt_tx_hash.clone().into_bytes())
+            .set("evt_index", BigInt::from(evt.evt_index))
+            .set("evt_block_time", BigInt::from(evt.evt_block_time))
+            .set("evt_block_number", BigInt::from(evt.evt_block_number))
+            .set("group_pool_address", Hex::decode(&evt.evt_address).unwrap() )
+            .set("lender", &evt.lender )
+            .set("shares_amount", BigDecimal::from_str(&evt.shares_amount).unwrap())
+            .set("prepared_at", BigInt::from(evt.prepared_at));
+    });
+    pool_shares_events.lendergroup_pool_shares_transfers.iter().for_each(|evt| {
+        tables
+            .create_row("group_pool_shares_transfer", format!("{}-{}", evt.evt_tx_hash, evt.evt_index))
+            .set("evt_tx_hash", evt.evt_tx_hash.clone().into_bytes())
+            .set("evt_index", BigInt::from(evt.evt_index))
+            .set("evt_block_time", BigInt::from(evt.evt_block_time))
+            .set("evt_block_number", BigInt::from(evt.evt_block_number))
+            .set("group_pool_address", Hex::decode(&evt.group_pool_address).unwrap() )
+            .set("pool_shares_token", Hex::decode(&evt.evt_address).unwrap() )
+            .set("from", &evt.from )
+            .set("to", &evt.to )
+            .set("amount", BigDecimal::from_str(&evt.amount).unwrap());
+    });
     events.lendergroup_initializeds.iter().for_each(|evt| {
         tables
             .create_row("group_initialized", format!("{}-{}", evt.evt_tx_hash, evt.evt_index))
@@ -681,6 +1204,7 @@ fn graph_lendergroup_out(
             .set("total_interest_collected",  BigInt::zero()) 
             .set("token_difference_from_liquidations",  fetched_token_amount_difference) 
             .set("total_collateral_withdrawn",  BigInt::zero()) 
+            .set("net_collateral_tokens_escrowed",  BigInt::zero()) 
            // .set("ordinal",   evt.log.ordinal  )  //is this ok ?  
             ;
 
@@ -833,6 +1357,8 @@ fn graph_lendergroup_out(
           
         }
          
+         let mut shares_exchange_rates_by_pool: HashMap<String, Option<rpc::SharesExchangeRatesFromRpc>> = HashMap::new();
+
          //need to use a non-delta store!?
          for group_pool_address in pool_metric_deltas_detected.iter() {
              
@@ -864,6 +1390,10 @@ fn graph_lendergroup_out(
             let total_collateral_withdrawn = store_collateral_withdrawn_data
             .get_at(ord, format!("total_collateral_amount_withdrawn:{}", group_pool_address) )
             .unwrap_or(BigInt::zero()) ;
+
+            let net_collateral_escrowed = store_pool_collateral_balance
+            .get_at(ord, format!("net_collateral_escrowed:{}", group_pool_address) )
+            .unwrap_or(BigInt::zero()) ;
                 
             let total_principal_tokens_withdrawn = store_get_lendergroup_pool_metrics
             .get_at(ord, format!("group_pool_metric:{}:total_principal_tokens_withdrawn", group_pool_address  ))
@@ -884,6 +1414,10 @@ fn graph_lendergroup_out(
 
 
             let fetched_token_amount_difference = rpc::fetch_token_amount_difference_from_liquidations(&group_pool_address.to_string()).unwrap_or_default();
+
+            let fetched_shares_exchange_rates = rpc::fetch_shares_exchange_rates_from_rpc(&group_pool_address.to_string());
+
+            let fetched_pool_capacity_data = rpc::fetch_pool_capacity_data_from_rpc(&group_pool_address.to_string());
          
                 
             /* let token_difference_from_liquidations = store_get_lendergroup_pool_metrics
@@ -905,6 +1439,7 @@ fn graph_lendergroup_out(
                     .set("total_principal_tokens_repaid", &total_principal_tokens_repaid  )
                     .set("total_interest_collected", &total_interest_collected )
                     .set("token_difference_from_liquidations",&fetched_token_amount_difference)
+                    .set("net_collateral_tokens_escrowed", &net_collateral_escrowed )
                     ;
             
                     
@@ -925,6 +1460,7 @@ fn graph_lendergroup_out(
                     .set("total_principal_tokens_repaid", &total_principal_tokens_repaid  )
                     .set("total_interest_collected", &total_interest_collected ) 
                     .set("token_difference_from_liquidations",&fetched_token_amount_difference)
+                    .set("net_collateral_tokens_escrowed", &net_collateral_escrowed )
                     ;
             
                 
@@ -945,7 +1481,36 @@ fn graph_lendergroup_out(
                     .set("total_principal_tokens_repaid", &total_principal_tokens_repaid  )
                     .set("total_interest_collected", &total_interest_collected )
                     .set("token_difference_from_liquidations",&fetched_token_amount_difference)
+                    .set("net_collateral_tokens_escrowed", &net_collateral_escrowed )
                     ;
+
+
+            // rpc snapshot history lives on the data points, the pool metric keeps the latest snapshot 
+            let snapshot_rows = [
+                ("group_pool_metric", group_pool_address.to_string()),
+                ("group_pool_metric_data_point", format!("{}_{}", group_pool_address, block_number )),
+                ("group_pool_metric_data_point_daily", format!("{}_{}", group_pool_address, day_index )),
+                ("group_pool_metric_data_point_weekly", format!("{}_{}", group_pool_address, week_index )),
+            ];
+
+            for (table_name, row_id) in snapshot_rows.iter() {
+
+                if let Some(shares_exchange_rates) = &fetched_shares_exchange_rates {
+                    tables.update_row(table_name, row_id)
+                        .set("shares_exchange_rate", &shares_exchange_rates.shares_exchange_rate )
+                        .set("shares_exchange_rate_inverse", &shares_exchange_rates.shares_exchange_rate_inverse );
+                }
+
+                if let Some(pool_capacity_data) = &fetched_pool_capacity_data {
+                    tables.update_row(table_name, row_id)
+                        .set("pool_utilization_ratio", &pool_capacity_data.pool_utilization_ratio )
+                        .set("pool_total_estimated_value", &pool_capacity_data.pool_total_estimated_value )
+                        .set("principal_amount_available_to_borrow", &pool_capacity_data.principal_amount_available_to_borrow )
+                        .set("total_principal_tokens_outstanding_in_active_loans", &pool_capacity_data.total_principal_tokens_outstanding_in_active_loans );
+                }
+            }
+
+            shares_exchange_rates_by_pool.insert(group_pool_address.to_string(), fetched_shares_exchange_rates);
                 
              
          }
@@ -960,56 +1525,125 @@ fn graph_lendergroup_out(
 
 
 
-    // -- start user metrics 
+    // -- start collateral balances 
+
+    for pool_collateral_delta in deltas_pool_collateral_balance.deltas.iter() {
+
+        let delta_root_identifier = substreams::key::segment_at(pool_collateral_delta.get_key(), 0);
+
+        if delta_root_identifier != "net_collateral_escrowed" {continue};
+
+        let group_pool_address = substreams::key::segment_at(pool_collateral_delta.get_key(), 1);
+
+        tables.update_row("group_pool_metric", group_pool_address)
+            .set("net_collateral_tokens_escrowed", &pool_collateral_delta.new_value );
+    }
+
+    let mut pool_collateral_assets_detected = HashSet::new();
+
+    for pool_collateral_delta in deltas_pool_collateral_balance.deltas.iter() {
+
+        let Some(pool_collateral_key) = parse_pool_collateral_asset_key(pool_collateral_delta.get_key()) else {continue};
+
+        pool_collateral_assets_detected.insert((
+            pool_collateral_key.group_pool_address,
+            pool_collateral_key.collateral_address,
+            pool_collateral_key.collateral_type,
+        ));
+    }
+
+    // fungible and nft collateral are both totalled per asset, nft token ids get their own rows below 
+    for (group_pool_address, collateral_address, collateral_type) in pool_collateral_assets_detected.iter() {
 
-   // let mut user_metric_deltas_detected = HashSet::new();
-         
-     
-    for user_metric_delta in deltas_lendergroup_user_metrics.deltas. iter(){
-    
-        
-        let delta_root_identifier = substreams::key::segment_at(user_metric_delta.get_key(), 0);
         let ord = 0; // FOR NOW - CAN CAUSE ISSUES 
-          
 
-        //maybe this is breaking things ?
-       if delta_root_identifier != "group_user_metric" {continue};
-        
-        let group_address = substreams::key::segment_at(user_metric_delta.get_key(), 1);
-        let user_address = substreams::key::segment_at(user_metric_delta.get_key(), 2);
-        let delta_prop_identifier = substreams::key::segment_at(user_metric_delta.get_key(), 3);
-                
-                
-                
-       // let block_number = BigInt::zero(); // FOR NOW 
-       
-        
-      //  user_metric_deltas_detected.insert(format!("{}:{}",group_address,user_address));
+        let net_amount_escrowed = store_pool_collateral_balance
+            .get_at(ord, format!("pool_collateral_asset_total:{}:{}:{}:net_escrowed", group_pool_address, collateral_address, collateral_type))
+            .unwrap_or(BigInt::zero());
+
+        let total_amount_withdrawn = store_collateral_withdrawn_data
+            .get_at(ord, format!("pool_collateral_asset_total:{}:{}:{}:withdrawn", group_pool_address, collateral_address, collateral_type))
+            .unwrap_or(BigInt::zero());
 
-        // if interaction count is 1, make a new row 
+        tables
+            .create_row("group_pool_collateral_asset", format!("{}_{}_{}", group_pool_address, collateral_address, collateral_type ))
+            .set("group_pool_address", Hex::decode( group_pool_address ).unwrap())
+            .set("collateral_address", Hex::decode( collateral_address ).unwrap())
+            .set("collateral_type", collateral_type.parse::<i32>().unwrap())
+            .set("net_amount_escrowed", &net_amount_escrowed )
+            .set("total_amount_withdrawn", &total_amount_withdrawn );
+    }
 
-        if delta_prop_identifier == "interaction_count"{
+    for token_id_delta in deltas_pool_collateral_token_ids.deltas.iter() {
 
-            let interaction_count = &user_metric_delta.new_value ;
+        let ord = 0; // FOR NOW - CAN CAUSE ISSUES 
 
-            if interaction_count == & BigInt::from(1) {
-                tables
-                .create_row("group_user_metric", format!("{}_{}", group_address, user_address )  ) 
-                .set("group_pool_address", Hex::decode( group_address ).unwrap())
-                .set("user_address", Hex::decode( user_address ).unwrap())
-      
-                .set("total_principal_tokens_committed", BigInt::zero() )
-                .set("total_collateral_tokens_escrowed", BigInt::zero() )
-                .set("total_principal_tokens_withdrawn", BigInt::zero() )
-                .set("total_principal_tokens_borrowed", BigInt::zero() );
-            }
-    
-            
+        let group_pool_address = substreams::key::segment_at(token_id_delta.get_key(), 1);
+        let collateral_address = substreams::key::segment_at(token_id_delta.get_key(), 2);
+        let collateral_type = substreams::key::segment_at(token_id_delta.get_key(), 3);
+        let token_id = substreams::key::segment_at(token_id_delta.get_key(), 4);
+
+        let escrowed = token_id_delta.operation != substreams::pb::substreams::store_delta::Operation::Delete;
+
+        let amount_escrowed = store_pool_collateral_balance
+            .get_at(ord, format!("pool_collateral_asset:{}:{}:{}:{}:net_escrowed", group_pool_address, collateral_address, collateral_type, token_id))
+            .unwrap_or(BigInt::zero());
+
+        tables
+            .create_row("group_pool_collateral_token", format!("{}_{}_{}_{}", group_pool_address, collateral_address, collateral_type, token_id ))
+            .set("group_pool_address", Hex::decode( group_pool_address ).unwrap())
+            .set("collateral_address", Hex::decode( collateral_address ).unwrap())
+            .set("collateral_type", collateral_type.parse::<i32>().unwrap())
+            .set("token_id", BigInt::from_str(token_id).unwrap())
+            .set("amount_escrowed", &amount_escrowed )
+            .set("escrowed", escrowed );
+    }
+
+    // -- end collateral balances 
+
+
+
+    // -- start user metrics 
+
+    /*
+        A user row is opened on the first metric delta of the block for that user, but only when neither
+        interaction_count nor shares_tokens_balance existed before this block. A secondary transfer recipient
+        gets their row from their first shares balance that way, and a later interaction just updates it.
+    */
+    let user_metric_existed_before_block = |user_metric_key: String| -> bool {
+        match deltas_lendergroup_user_metrics.deltas.iter().find(|user_metric_delta| user_metric_delta.key == user_metric_key) {
+            Some(first_user_metric_delta) => first_user_metric_delta.operation != substreams::pb::substreams::store_delta::Operation::Create,
+            None => store_get_lendergroup_user_metrics.has_last(user_metric_key),
         }
-    
-     
+    };
 
-       
+    let mut users_with_metric_deltas = HashSet::new();
+
+    for user_metric_delta in deltas_lendergroup_user_metrics.deltas.iter() {
+
+        let Some((group_address, user_address, _)) = parse_group_user_metric_key(user_metric_delta.get_key()) else {continue};
+
+        // only the first delta of each user in the block decides whether the row is new
+        if !users_with_metric_deltas.insert((group_address, user_address)) {continue};
+
+        if user_metric_delta.old_value != BigInt::zero() {continue};
+
+        let is_new_user = !user_metric_existed_before_block(format!("group_user_metric:{}:{}:interaction_count", group_address, user_address))
+            && !user_metric_existed_before_block(format!("group_user_metric:{}:{}:shares_tokens_balance", group_address, user_address));
+
+        if !is_new_user {continue};
+
+        tables
+            .create_row("group_user_metric", format!("{}_{}", group_address, user_address )  ) 
+            .set("group_pool_address", Hex::decode( group_address ).unwrap())
+            .set("user_address", Hex::decode( user_address ).unwrap())
+
+            .set("total_principal_tokens_committed", BigInt::zero() )
+            .set("total_collateral_tokens_escrowed", BigInt::zero() )
+            .set("total_principal_tokens_withdrawn", BigInt::zero() )
+            .set("total_principal_tokens_borrowed", BigInt::zero() )
+            .set("shares_tokens_balance", BigInt::zero() )
+            .set("shares_balance_value", BigInt::zero() );
     }
   
 
@@ -1057,6 +1691,10 @@ fn graph_lendergroup_out(
             tables.update_row("group_user_metric", format!("{}_{}", group_address, user_address ))
                 .set("total_interest_collected", new_value );
         },
+        "shares_tokens_balance" => {
+            tables.update_row("group_user_metric", format!("{}_{}", group_address, user_address ))
+                .set("shares_tokens_balance", new_value );
+        },
         
         
         // Add more cases as per your metric names
@@ -1072,6 +1710,336 @@ fn graph_lendergroup_out(
     // -- end user metrics 
 
 
+
+    // -- start user positions 
+
+    /*
+        A position is worth  shares * shares_exchange_rate / expansion factor  in principal tokens. 
+        Revalue every current holder whose own metrics moved this block, plus holders whose shares just went to zero. 
+        When a pool took a new exchange rate snapshot this block, every current holder of that pool is revalued as well. 
+    */
+    let mut positions_to_value = HashSet::new();
+
+    for user_metric_delta in deltas_lendergroup_user_metrics.deltas.iter() {
+
+        let Some((group_address, user_address, delta_prop_identifier)) = parse_group_user_metric_key(user_metric_delta.get_key()) else {continue};
+
+        let is_share_holder = store_pool_share_holders
+            .has_last(format!("pool_share_holder:{}:{}", group_address, user_address));
+
+        if is_share_holder || delta_prop_identifier == "shares_tokens_balance" {
+            positions_to_value.insert((group_address.to_string(), user_address.to_string()));
+        }
+    }
+
+    for (group_pool_address, fetched_shares_exchange_rates) in shares_exchange_rates_by_pool.iter() {
+
+        if fetched_shares_exchange_rates.is_none() {continue};
+
+        let pool_share_holder_list = store_pool_share_holder_lists
+            .get_last(format!("pool_share_holder_list:{}", group_pool_address))
+            .unwrap_or_default();
+
+        for user_address in pool_share_holder_list {
+
+            // the list keeps wallets that sold out since, only the current holders are revalued 
+            if !store_pool_share_holders.has_last(format!("pool_share_holder:{}:{}", group_pool_address, user_address)) {continue};
+
+            positions_to_value.insert((group_pool_address.clone(), user_address));
+        }
+    }
+
+    let ord = 0; // FOR NOW - CAN CAUSE ISSUES 
+
+    // secondary share transfers do not touch the globals store, so prefer their own block data 
+    let (block_number, block_time) = match pool_shares_events.lendergroup_pool_shares_transfers.first() {
+        Some(evt) => (BigInt::from(evt.evt_block_number), BigInt::from(evt.evt_block_time)),
+        None => (
+            store_get_globals.get_at(ord, "latest_block_number").unwrap_or(BigInt::zero()),
+            store_get_globals.get_at(ord, "latest_block_time").unwrap_or(BigInt::zero()),
+        ),
+    };
+
+    let day_index = block_time.clone() / 86400;
+
+    for (group_pool_address, user_address) in positions_to_value.iter() {
+
+        let fetched_shares_exchange_rates = shares_exchange_rates_by_pool
+            .entry(group_pool_address.clone())
+            .or_insert_with(|| rpc::fetch_shares_exchange_rates_from_rpc(group_pool_address));
+
+        let Some(shares_exchange_rates) = fetched_shares_exchange_rates else {continue};
+
+        let shares_tokens_balance = store_get_lendergroup_user_metrics
+            .get_at(ord, format!("group_user_metric:{}:{}:shares_tokens_balance", group_pool_address, user_address))
+            .unwrap_or(BigInt::zero());
+
+        let total_principal_tokens_committed = store_get_lendergroup_user_metrics
+            .get_at(ord, format!("group_user_metric:{}:{}:total_principal_tokens_committed", group_pool_address, user_address))
+            .unwrap_or(BigInt::zero());
+
+        let total_principal_tokens_withdrawn = store_get_lendergroup_user_metrics
+            .get_at(ord, format!("group_user_metric:{}:{}:total_principal_tokens_withdrawn", group_pool_address, user_address))
+            .unwrap_or(BigInt::zero());
+
+        let shares_balance_value = shares_position_value(
+            &shares_tokens_balance,
+            &shares_exchange_rates.shares_exchange_rate,
+            &shares_exchange_rates.exchange_rate_expansion_factor,
+        );
+
+        let has_secondary_share_transfers = store_get_lendergroup_user_metrics
+            .get_at(ord, format!("group_user_metric:{}:{}:secondary_share_transfer_count", group_pool_address, user_address))
+            .is_some();
+
+        let total_interest_earned = lender_interest_earned(
+            &shares_balance_value,
+            &total_principal_tokens_withdrawn,
+            &total_principal_tokens_committed,
+            has_secondary_share_transfers,
+        );
+
+        let user_metric_row = tables.update_row("group_user_metric", format!("{}_{}", group_pool_address, user_address ))
+            .set("shares_balance_value", &shares_balance_value );
+
+        if let Some(total_interest_earned) = &total_interest_earned {
+            user_metric_row.set("total_interest_earned", total_interest_earned );
+        }
+
+        let data_point_row = tables
+            .create_row("group_user_metric_data_point_daily", format!("{}_{}_{}", group_pool_address, user_address, day_index ))
+            .set("group_pool_address", Hex::decode( group_pool_address ).unwrap())
+            .set("user_address", Hex::decode( user_address ).unwrap())
+            .set("block_number", &block_number )
+            .set("block_time", &block_time )
+            .set("shares_tokens_balance", &shares_tokens_balance )
+            .set("shares_exchange_rate", &shares_exchange_rates.shares_exchange_rate )
+            .set("shares_balance_value", &shares_balance_value )
+            .set("total_principal_tokens_committed", &total_principal_tokens_committed )
+            .set("total_principal_tokens_withdrawn", &total_principal_tokens_withdrawn );
+
+        if let Some(total_interest_earned) = &total_interest_earned {
+            data_point_row.set("total_interest_earned", total_interest_earned );
+        }
+    }
+
+    // -- end user positions 
+
+
+
+    // -- start group loans 
+
+    events.lendergroup_borrower_accepted_funds.iter().for_each(|evt| {
+        tables
+            .create_row("group_loan", &evt.bid_id)
+            .set("bid_id", BigInt::from_str(&evt.bid_id).unwrap())
+            .set("group_pool_address", Hex::decode(&evt.evt_address).unwrap() )
+            .set("borrower", &evt.borrower )
+            .set("principal_amount", BigInt::from_str(&evt.principal_amount).unwrap())
+            .set("collateral_amount", BigInt::from_str(&evt.collateral_amount).unwrap())
+            .set("interest_rate", evt.interest_rate)
+            .set("loan_duration", evt.loan_duration)
+            .set("accepted_tx_hash", evt.evt_tx_hash.clone().into_bytes())
+            .set("accepted_block_number", BigInt::from(evt.evt_block_number))
+            .set("accepted_at", BigInt::from(evt.evt_block_time))
+            .set("status", "active")
+            .set("total_principal_repaid", BigInt::zero())
+            .set("total_interest_repaid", BigInt::zero())
+            .set("repayment_count", BigInt::zero());
+    });
+
+    for group_loan_delta in deltas_group_loan_repayments.deltas.iter() {
+
+        let delta_root_identifier = substreams::key::segment_at(group_loan_delta.get_key(), 0);
+
+        if delta_root_identifier != "group_loan" {continue};
+
+        let bid_id = substreams::key::segment_at(group_loan_delta.get_key(), 1);
+        let delta_prop_identifier = substreams::key::segment_at(group_loan_delta.get_key(), 2);
+
+        tables.update_row("group_loan", bid_id)
+            .set(delta_prop_identifier, &group_loan_delta.new_value );
+    }
+
+    events.lendergroup_loan_repaids.iter().for_each(|evt| {
+        tables.update_row("group_loan", &evt.bid_id)
+            .set("last_repaid_at", BigInt::from(evt.evt_block_time));
+    });
+
+    events.lendergroup_defaulted_loan_liquidateds.iter().for_each(|evt| {
+        tables.update_row("group_loan", &evt.bid_id)
+            .set("status", "liquidated")
+            .set("liquidator", &evt.liquidator )
+            .set("liquidation_amount_due", BigInt::from_str(&evt.amount_due).unwrap())
+            .set("liquidation_token_amount_difference", BigInt::from_str(&evt.token_amount_difference).unwrap())
+            .set("liquidated_tx_hash", evt.evt_tx_hash.clone().into_bytes())
+            .set("liquidated_at", BigInt::from(evt.evt_block_time));
+    });
+
+    // TellerV2 side of the loan: who funded it, direct repayments and due dates the pool events don't carry 
+    tellerv2_events.tellerv2_accepted_bids.iter().for_each(|evt| {
+        tables.update_row("group_loan", &evt.bid_id)
+            .set("teller_v2_address", Hex::decode(&evt.evt_address).unwrap() )
+            .set("lender", &evt.lender );
+    });
+
+    let mut teller_v2_loans_detected = HashSet::new();
+
+    tellerv2_events.tellerv2_accepted_bids.iter().for_each(|evt| {
+        teller_v2_loans_detected.insert((evt.evt_address.clone(), evt.bid_id.clone()));
+    });
+
+    tellerv2_events.tellerv2_loan_repayments.iter().for_each(|evt| {
+        tables.update_row("group_loan", &evt.bid_id)
+            .set("last_teller_v2_repayment_tx_hash", evt.evt_tx_hash.clone().into_bytes());
+
+        teller_v2_loans_detected.insert((evt.evt_address.clone(), evt.bid_id.clone()));
+    });
+
+    tellerv2_events.tellerv2_loan_repaids.iter().for_each(|evt| {
+        tables.update_row("group_loan", &evt.bid_id)
+            .set("status", "repaid")
+            .set("repaid_tx_hash", evt.evt_tx_hash.clone().into_bytes())
+            .set("repaid_at", BigInt::from(evt.evt_block_time));
+    });
+
+    tellerv2_events.tellerv2_loan_liquidateds.iter().for_each(|evt| {
+        tables.update_row("group_loan", &evt.bid_id)
+            .set("status", "liquidated")
+            .set("liquidator", &evt.liquidator )
+            .set("liquidated_tx_hash", evt.evt_tx_hash.clone().into_bytes())
+            .set("liquidated_at", BigInt::from(evt.evt_block_time));
+    });
+
+    // a closed loan has no due dates left to read 
+    let closed_bid_ids: HashSet<&String> = tellerv2_events.tellerv2_loan_repaids.iter().map(|evt| &evt.bid_id)
+        .chain(tellerv2_events.tellerv2_loan_liquidateds.iter().map(|evt| &evt.bid_id))
+        .chain(events.lendergroup_defaulted_loan_liquidateds.iter().map(|evt| &evt.bid_id))
+        .collect();
+
+    for (teller_v2_address, bid_id) in teller_v2_loans_detected.iter() {
+
+        if closed_bid_ids.contains(bid_id) {continue};
+
+        if let Some(loan_due_dates) = rpc::fetch_loan_due_dates_from_rpc(teller_v2_address, bid_id) {
+            tables.update_row("group_loan", bid_id)
+                .set("next_due_date", &loan_due_dates.next_due_date )
+                .set("default_timestamp", &loan_due_dates.default_timestamp )
+                .set("last_repaid_timestamp", &loan_due_dates.last_repaid_timestamp );
+        }
+    }
+
+    // -- end group loans 
+
+
+
+    // -- start withdrawal requests 
+
+    /*
+        Preparing shares (re)opens the request for that lender, so the row is always rewritten.
+        The withdrawl delay is read from the pool so we can forecast when the shares become burnable. 
+        Prepares and withdrawals share the row, so they are folded in log order like store_withdrawal_requests
+        and each request row is written once at the end.
+    */
+    enum WithdrawalRequestAction<'a> {
+        Prepared(&'a contract::LendergroupPoolSharesPrepared),
+        Withdrawn(&'a contract::LendergroupEarningsWithdrawn),
+    }
+
+    #[derive(Default)]
+    struct WithdrawalRequestState<'a> {
+        prepared: Option<&'a contract::LendergroupPoolSharesPrepared>,
+        fulfilled: Option<&'a contract::LendergroupEarningsWithdrawn>,
+    }
+
+    let mut withdrawal_request_actions: Vec<(u32, WithdrawalRequestAction)> = Vec::new();
+
+    events.lendergroup_pool_shares_prepareds.iter().for_each(|evt| {
+        withdrawal_request_actions.push((evt.evt_index, WithdrawalRequestAction::Prepared(evt)));
+    });
+
+    events.lendergroup_earnings_withdrawns.iter().for_each(|evt| {
+        withdrawal_request_actions.push((evt.evt_index, WithdrawalRequestAction::Withdrawn(evt)));
+    });
+
+    withdrawal_request_actions.sort_by_key(|(evt_index, _)| *evt_index);
+
+    let mut withdrawal_request_states: BTreeMap<String, WithdrawalRequestState> = BTreeMap::new();
+
+    for (evt_index, action) in withdrawal_request_actions {
+
+        match action {
+            WithdrawalRequestAction::Prepared(evt) => {
+
+                let lender_address = Hex(&evt.lender).to_string();
+
+                // a new prepare replaces whatever the request went through earlier in the block
+                withdrawal_request_states.insert(
+                    format!("{}_{}", evt.evt_address, lender_address ),
+                    WithdrawalRequestState { prepared: Some(evt), fulfilled: None },
+                );
+            },
+            WithdrawalRequestAction::Withdrawn(evt) => {
+
+                let lender_address = Hex(&evt.lender).to_string();
+
+                let request_key = format!("withdrawal_request:{}:{}", evt.evt_address, lender_address);
+
+                // a delete on the request key at this ordinal means the withdrawal consumed a pending request
+                let consumed_pending_request = deltas_withdrawal_requests.deltas.iter().any(|withdrawal_request_delta|
+                    withdrawal_request_delta.operation == substreams::pb::substreams::store_delta::Operation::Delete
+                        && withdrawal_request_delta.key == request_key
+                        && withdrawal_request_delta.ordinal == evt_index as u64
+                );
+
+                if !consumed_pending_request {continue};
+
+                withdrawal_request_states
+                    .entry(format!("{}_{}", evt.evt_address, lender_address ))
+                    .or_default()
+                    .fulfilled = Some(evt);
+            },
+        }
+    }
+
+    for (withdrawal_request_id, withdrawal_request_state) in withdrawal_request_states {
+
+        // the row only exists already when it was not (re)prepared in this block
+        let withdrawal_request_row = match withdrawal_request_state.prepared {
+            Some(evt) => {
+                let withdrawal_request_row = tables
+                    .create_row("group_withdrawal_request", withdrawal_request_id)
+                    .set("group_pool_address", Hex::decode(&evt.evt_address).unwrap() )
+                    .set("lender", &evt.lender )
+                    .set("prepared_shares", BigInt::from_str(&evt.shares_amount).unwrap())
+                    .set("prepared_at", BigInt::from(evt.prepared_at))
+                    .set("prepared_tx_hash", evt.evt_tx_hash.clone().into_bytes())
+                    .set("fulfilled", withdrawal_request_state.fulfilled.is_some());
+
+                // a failed delay call leaves the forecast unset rather than pretending the delay is zero
+                if let Some(withdrawl_delay_time_seconds) = rpc::fetch_withdrawl_delay_time_seconds_from_rpc(
+                    &evt.evt_address
+                ) {
+                    withdrawal_request_row.set("withdrawable_at", BigInt::from(evt.prepared_at) + withdrawl_delay_time_seconds);
+                }
+
+                withdrawal_request_row
+            },
+            None => tables.update_row("group_withdrawal_request", withdrawal_request_id),
+        };
+
+        if let Some(evt) = withdrawal_request_state.fulfilled {
+            withdrawal_request_row
+                .set("fulfilled", true)
+                .set("fulfilled_at", BigInt::from(evt.evt_block_time))
+                .set("fulfilled_tx_hash", evt.evt_tx_hash.clone().into_bytes())
+                .set("fulfilled_shares_amount", BigInt::from_str(&evt.amount_pool_shares_tokens).unwrap());
+        }
+    }
+
+    // -- end withdrawal requests 
+
+
 }
 
 
@@ -1103,16 +2071,134 @@ fn store_accepted_bids_data(
 
 
 
+/*
+
+    Each bid gets its own CollateralEscrow, deployed by the CollateralManager.
+    Register them here (like store_factory_lendergroup_created) so their own events can be decoded.
+
+*/
+#[substreams::handlers::store]
+fn store_collateral_escrows_created(blk: eth::Block, store: StoreSetString) {
+    for rcpt in blk.receipts() {
+        for log in rcpt
+            .receipt
+            .logs
+            .iter()
+            .filter(|log| log.address == COLLATERAL_MANAGER_TRACKED_CONTRACT)
+        {
+            if let Some(event) = abi::collateral_manager::events::CollateralEscrowDeployed::match_and_decode(log) {
+                 store.set(0, format!("collateral_escrow:{}", Hex(event.u_collateral_escrow).to_string()), &event.u_bid_id.to_string());
+            }
+        }
+    }
+}
+
+
 #[substreams::handlers::map]
 fn map_collateralmanager_events(
     blk: eth::Block,
-    //store_collateral_withdrawn: StoreGetInt64,
+    store_collateral_escrows: StoreGetString,
 ) -> Result<collateral_contract::Events, substreams::errors::Error> {
     let mut collateral_events = collateral_contract::Events::default();
     //map_factory_events(&blk, &mut events);
     //map_lendergroup_events(&blk, &store_lendergroup, &mut events);
 
 
+    collateral_events.collateral_manager_collateral_escrow_deployeds.append(&mut blk
+        .receipts()
+        .flat_map(|view| {
+            view.receipt.logs.iter()
+                .filter(|log| log.address == COLLATERAL_MANAGER_TRACKED_CONTRACT)
+                .filter_map(|log| {
+                    if let Some(event) = abi::collateral_manager::events::CollateralEscrowDeployed::match_and_decode(log) {
+                        return Some(collateral_contract::CollateralmanagerCollateralEscrowDeployed {
+                            evt_tx_hash: Hex(&view.transaction.hash).to_string(),
+                            evt_index: log.block_index,
+                            evt_block_time: blk.timestamp_seconds(),
+                            evt_block_number: blk.number,
+                            bid_id: event.u_bid_id.to_string(),
+                            collateral_escrow: event.u_collateral_escrow,
+                        });
+                    }
+
+                    None
+                })
+        })
+        .collect());
+
+    collateral_events.collateral_manager_collateral_committed.append(&mut blk
+        .receipts()
+        .flat_map(|view| {
+            view.receipt.logs.iter()
+                .filter(|log| log.address == COLLATERAL_MANAGER_TRACKED_CONTRACT)
+                .filter_map(|log| {
+                    if let Some(event) = abi::collateral_manager::events::CollateralCommitted::match_and_decode(log) {
+                        return Some(collateral_contract::CollateralmanagerCollateralCommitted {
+                            evt_tx_hash: Hex(&view.transaction.hash).to_string(),
+                            evt_index: log.block_index,
+                            evt_block_time: blk.timestamp_seconds(),
+                            evt_block_number: blk.number,
+                            bid_id: event.u_bid_id.to_string(),
+                            collateral_type: event.u_type.to_string().parse().unwrap(), //coerce into a u32 ..
+                            collateral_address: event.u_collateral_address,
+                            amount: event.u_amount.to_string(),
+                            token_id: event.u_token_id.to_string(),
+                        });
+                    }
+
+                    None
+                })
+        })
+        .collect());
+
+    collateral_events.collateral_manager_collateral_deposited.append(&mut blk
+        .receipts()
+        .flat_map(|view| {
+            view.receipt.logs.iter()
+                .filter(|log| log.address == COLLATERAL_MANAGER_TRACKED_CONTRACT)
+                .filter_map(|log| {
+                    if let Some(event) = abi::collateral_manager::events::CollateralDeposited::match_and_decode(log) {
+                        return Some(collateral_contract::CollateralmanagerCollateralDeposited {
+                            evt_tx_hash: Hex(&view.transaction.hash).to_string(),
+                            evt_index: log.block_index,
+                            evt_block_time: blk.timestamp_seconds(),
+                            evt_block_number: blk.number,
+                            bid_id: event.u_bid_id.to_string(),
+                            collateral_type: event.u_type.to_string().parse().unwrap(), //coerce into a u32 ..
+                            collateral_address: event.u_collateral_address,
+                            amount: event.u_amount.to_string(),
+                            token_id: event.u_token_id.to_string(),
+                        });
+                    }
+
+                    None
+                })
+        })
+        .collect());
+
+
+    collateral_events.collateral_manager_collateral_claimed.append(&mut blk
+        .receipts()
+        .flat_map(|view| {
+            view.receipt.logs.iter()
+                .filter(|log| log.address == COLLATERAL_MANAGER_TRACKED_CONTRACT)
+                .filter_map(|log| {
+                    if let Some(event) = abi::collateral_manager::events::CollateralClaimed::match_and_decode(log) {
+                        return Some(collateral_contract::CollateralmanagerCollateralClaimed {
+                            evt_tx_hash: Hex(&view.transaction.hash).to_string(),
+                            evt_index: log.block_index,
+                            evt_block_time: blk.timestamp_seconds(),
+                            evt_block_number: blk.number,
+                            bid_id: event.u_bid_id.to_string(),
+                        });
+                    }
+
+                    None
+                })
+        })
+        .collect());
+
+
     collateral_events.collateral_manager_collateral_withdrawn.append(&mut blk
         .receipts()
         .flat_map(|view| {
@@ -1148,6 +2234,56 @@ fn map_collateralmanager_events(
                 })
         })
         .collect());
+
+
+    // the escrows' own events, so collateral held per bid can be checked independently of the manager 
+    collateral_events.collateral_escrow_collateral_deposited.append(&mut blk
+        .receipts()
+        .flat_map(|view| {
+            view.receipt.logs.iter()
+                .filter_map(|log| {
+                    // decode first so the escrow lookup only runs for logs shaped like escrow events 
+                    let event = abi::collateral_escrow::events::CollateralDeposited::match_and_decode(log)?;
+
+                    let bid_id = store_collateral_escrows.get_last(format!("collateral_escrow:{}", Hex(&log.address).to_string()))?;
+
+                    Some(collateral_contract::CollateralescrowCollateralDeposited {
+                        evt_tx_hash: Hex(&view.transaction.hash).to_string(),
+                        evt_index: log.block_index,
+                        evt_block_time: blk.timestamp_seconds(),
+                        evt_block_number: blk.number,
+                        evt_address: log.address.clone(),
+                        bid_id,
+                        collateral_address: event.u_collateral_address,
+                        amount: event.u_amount.to_string(),
+                    })
+                })
+        })
+        .collect());
+
+    collateral_events.collateral_escrow_collateral_withdrawn.append(&mut blk
+        .receipts()
+        .flat_map(|view| {
+            view.receipt.logs.iter()
+                .filter_map(|log| {
+                    let event = abi::collateral_escrow::events::CollateralWithdrawn::match_and_decode(log)?;
+
+                    let bid_id = store_collateral_escrows.get_last(format!("collateral_escrow:{}", Hex(&log.address).to_string()))?;
+
+                    Some(collateral_contract::CollateralescrowCollateralWithdrawn {
+                        evt_tx_hash: Hex(&view.transaction.hash).to_string(),
+                        evt_index: log.block_index,
+                        evt_block_time: blk.timestamp_seconds(),
+                        evt_block_number: blk.number,
+                        evt_address: log.address.clone(),
+                        bid_id,
+                        collateral_address: event.u_collateral_address,
+                        amount: event.u_amount.to_string(),
+                        recipient: event.u_recipient,
+                    })
+                })
+        })
+        .collect());
   
     
     Ok(collateral_events)
@@ -1172,7 +2308,7 @@ fn store_bid_collateral_withdrawn_data_deltas(
     events.collateral_manager_collateral_withdrawn.iter().for_each(|evt: &collateral_contract::CollateralmanagerCollateralWithdrawn| {
         
 
-        let store_key: String = format!("collateral_amount_withdrawn:{}:{}", evt.bid_id,Hex(&evt.collateral_address).to_string());
+        let store_key: String = format!("collateral_amount_withdrawn:{}:{}:{}:{}", evt.bid_id,Hex(&evt.collateral_address).to_string(), evt.collateral_type, evt.token_id);
         bigint_delta_store.add(ord,&store_key, BigInt::from_str(&evt.amount).unwrap_or(BigInt::zero()));
 
         substreams::log::info!(" Storing collateral amt withdrawn: {} {}",store_key, BigInt::from_str(&evt.amount).unwrap_or(BigInt::zero()) );
@@ -1199,57 +2335,241 @@ fn store_pool_collateral_withdrawn_data(
 
 
 
-    /*  for each delta,it tells how much collateral was withdrawn for a bid 
+    /*  for each delta,it tells how much collateral was withdrawn for a bid 
+
+    We have to look up the pool address  using the bid id with string_get_store 
+
+    Then we have to store, in the output store, an addition 
+
+
+
+    */
+    for collateral_withdrawn_delta in bigint_delta_store.deltas.iter(){
+             
+                            
+                //this splits on ":"
+        let delta_root_identifier = substreams::key::segment_at(collateral_withdrawn_delta.get_key(), 0);
+
+        if delta_root_identifier != "collateral_amount_withdrawn" {continue};
+
+        let bid_id = substreams::key::segment_at(collateral_withdrawn_delta.get_key(), 1);
+        let collateral_address = substreams::key::segment_at(collateral_withdrawn_delta.get_key(), 2);
+        let collateral_type = substreams::key::segment_at(collateral_withdrawn_delta.get_key(), 3);
+        let token_id = substreams::key::segment_at(collateral_withdrawn_delta.get_key(), 4);
+        
+                
+        //  let block_number = 0; // FOR NOW 
+      //  let new_value = &pool_metric_delta.new_value ;
+
+      let delta_value = collateral_withdrawn_delta.new_value.clone() - collateral_withdrawn_delta.old_value.clone();
+       // let delta_value = &collateral_withdrawn_delta.delta_value; // ??? 
+
+
+      
+
+         let string_store_key = format!("bid_originated_from_pool:{}", bid_id);
+         if let Some( group_pool_address ) = string_get_store.get_at(ord, string_store_key){
+
+            // the pool total stays fungible, nft collateral is only accounted per asset and token id 
+            if is_fungible_collateral(collateral_type) {
+                let output_store_key = format!("total_collateral_amount_withdrawn:{}", group_pool_address);
+
+                output_store.add(ord, &output_store_key, delta_value.clone() );
+            }
+
+            let output_store_key = format!("pool_collateral_asset:{}:{}:{}:{}:withdrawn", group_pool_address, collateral_address, collateral_type, token_id);
+
+            output_store.add(ord, &output_store_key, delta_value.clone() );
+
+            let output_store_key = format!("pool_collateral_asset_total:{}:{}:{}:withdrawn", group_pool_address, collateral_address, collateral_type);
+
+            output_store.add(ord, &output_store_key, delta_value.clone() );
+
+
+         }
+                    
+
+      }
+
+     
+}
+
+
+
+
+ 
+ 
+
+
+
+/*
+
+    Net collateral held for a bid: credited on deposit, debited on every withdrawal. 
+    Withdrawals to the lender or liquidator after a default are also CollateralWithdrawn, so claims are covered too.
+
+*/
+#[substreams::handlers::store]
+fn store_bid_collateral_balance_deltas(
+    events:  collateral_contract::Events,    
+    bigint_add_store:  StoreAddBigInt
+) {
+
+    let ord = 0; // FOR NOW - CAN CAUSE ISSUES - GET FROM LOG AND STUFF INTO EVENT    
+
+    events.collateral_manager_collateral_deposited.iter().for_each(|evt: &collateral_contract::CollateralmanagerCollateralDeposited| {
+
+        let store_key: String = format!("collateral_balance:{}:{}:{}:{}", evt.bid_id,Hex(&evt.collateral_address).to_string(), evt.collateral_type, evt.token_id);
+        bigint_add_store.add(ord,&store_key, BigInt::from_str(&evt.amount).unwrap_or(BigInt::zero()));
+
+    });
+
+    events.collateral_manager_collateral_withdrawn.iter().for_each(|evt: &collateral_contract::CollateralmanagerCollateralWithdrawn| {
+
+        let store_key: String = format!("collateral_balance:{}:{}:{}:{}", evt.bid_id,Hex(&evt.collateral_address).to_string(), evt.collateral_type, evt.token_id);
+        bigint_add_store.add(ord,&store_key, BigInt::zero() - BigInt::from_str(&evt.amount).unwrap_or(BigInt::zero()));
+
+    });
+
+}
+
+// nft token ids still escrowed per pool and asset, deleted once the pool no longer holds any of the token 
+#[substreams::handlers::store]
+fn store_pool_collateral_token_ids(
+    deltas_pool_collateral_balance: Deltas<DeltaBigInt>,
+
+    string_set_store: StoreSetString,
+) {
+
+    for pool_collateral_delta in deltas_pool_collateral_balance.deltas.iter(){
+
+        let Some(pool_collateral_key) = parse_pool_collateral_asset_key(pool_collateral_delta.get_key()) else {continue};
+
+        if pool_collateral_key.metric != "net_escrowed" {continue};
+        if is_fungible_collateral(pool_collateral_key.collateral_type) {continue};
+
+        let token_id = pool_collateral_key.token_id;
+
+        let store_key = format!("pool_collateral_token_id:{}:{}:{}:{}",
+            pool_collateral_key.group_pool_address, pool_collateral_key.collateral_address, pool_collateral_key.collateral_type, token_id);
+
+        if pool_collateral_delta.new_value > BigInt::zero() {
+            string_set_store.set(pool_collateral_delta.ordinal, &store_key, &token_id.to_string());
+        } else {
+            string_set_store.delete_prefix(pool_collateral_delta.ordinal as i64, &store_key);
+        }
+
+    }
+
+}
+
+/*
+
+    Running committed / deposited / withdrawn / claimed amounts per bid, collateral asset, collateral type and token id. 
+    CollateralClaimed only carries the bid id, so withdrawals in the same tx as a claim are counted as claimed.
+
+*/
+#[substreams::handlers::store]
+fn store_bid_collateral_amounts(
+    events:  collateral_contract::Events,    
+    bigint_add_store:  StoreAddBigInt
+) {
+
+    let ord = 0; // FOR NOW - CAN CAUSE ISSUES - GET FROM LOG AND STUFF INTO EVENT    
+
+    let claimed_bids: HashSet<(&String, &String)> = events.collateral_manager_collateral_claimed.iter()
+        .map(|evt| (&evt.evt_tx_hash, &evt.bid_id))
+        .collect();
+
+    events.collateral_manager_collateral_committed.iter().for_each(|evt: &collateral_contract::CollateralmanagerCollateralCommitted| {
+
+        let store_key: String = format!("bid_collateral:{}:{}:{}:{}:committed", evt.bid_id,Hex(&evt.collateral_address).to_string(), evt.collateral_type, evt.token_id);
+        bigint_add_store.add(ord,&store_key, BigInt::from_str(&evt.amount).unwrap_or(BigInt::zero()));
+
+    });
+
+    events.collateral_manager_collateral_deposited.iter().for_each(|evt: &collateral_contract::CollateralmanagerCollateralDeposited| {
+
+        let store_key: String = format!("bid_collateral:{}:{}:{}:{}:deposited", evt.bid_id,Hex(&evt.collateral_address).to_string(), evt.collateral_type, evt.token_id);
+        bigint_add_store.add(ord,&store_key, BigInt::from_str(&evt.amount).unwrap_or(BigInt::zero()));
+
+    });
+
+    events.collateral_manager_collateral_withdrawn.iter().for_each(|evt: &collateral_contract::CollateralmanagerCollateralWithdrawn| {
+
+        let amount = BigInt::from_str(&evt.amount).unwrap_or(BigInt::zero());
+
+        let store_key: String = format!("bid_collateral:{}:{}:{}:{}:withdrawn", evt.bid_id,Hex(&evt.collateral_address).to_string(), evt.collateral_type, evt.token_id);
+        bigint_add_store.add(ord,&store_key, amount.clone());
+
+        if claimed_bids.contains(&(&evt.evt_tx_hash, &evt.bid_id)) {
+            let store_key: String = format!("bid_collateral:{}:{}:{}:{}:claimed", evt.bid_id,Hex(&evt.collateral_address).to_string(), evt.collateral_type, evt.token_id);
+            bigint_add_store.add(ord,&store_key, amount);
+        }
+
+    });
+
+    // the escrow contract only reports the collateral address, so its balance is kept per asset across token ids 
+    events.collateral_escrow_collateral_deposited.iter().for_each(|evt: &collateral_contract::CollateralescrowCollateralDeposited| {
+
+        let store_key: String = format!("bid_collateral_escrow_balance:{}:{}", evt.bid_id,Hex(&evt.collateral_address).to_string());
+        bigint_add_store.add(ord,&store_key, BigInt::from_str(&evt.amount).unwrap_or(BigInt::zero()));
+
+    });
+
+    events.collateral_escrow_collateral_withdrawn.iter().for_each(|evt: &collateral_contract::CollateralescrowCollateralWithdrawn| {
 
-    We have to look up the pool address  using the bid id with string_get_store 
+        let store_key: String = format!("bid_collateral_escrow_balance:{}:{}", evt.bid_id,Hex(&evt.collateral_address).to_string());
+        bigint_add_store.add(ord,&store_key, BigInt::zero() - BigInt::from_str(&evt.amount).unwrap_or(BigInt::zero()));
 
-    Then we have to store, in the output store, an addition 
+    });
 
+}
 
+#[substreams::handlers::store]
+fn store_pool_collateral_balance(
+    bigint_delta_store: Deltas<DeltaBigInt> ,
+    string_get_store: StoreGetString, 
 
-    */
-    for collateral_withdrawn_delta in bigint_delta_store.deltas.iter(){
-             
-                            
-                //this splits on ":"
-        let delta_root_identifier = substreams::key::segment_at(collateral_withdrawn_delta.get_key(), 0);
+    output_store: StoreAddBigInt,
+) {
 
-        if delta_root_identifier != "collateral_amount_withdrawn" {continue};
+    let ord = 0; // FOR NOW - CAN CAUSE ISSUES - GET FROM LOG AND STUFF INTO EVENT    
 
-        let bid_id = substreams::key::segment_at(collateral_withdrawn_delta.get_key(), 1);
-        let collateral_address = substreams::key::segment_at(collateral_withdrawn_delta.get_key(), 2); //ignore for now 
-        
-                
-        //  let block_number = 0; // FOR NOW 
-      //  let new_value = &pool_metric_delta.new_value ;
+    for collateral_balance_delta in bigint_delta_store.deltas.iter(){
 
-      let delta_value = collateral_withdrawn_delta.new_value.clone() - collateral_withdrawn_delta.old_value.clone();
-       // let delta_value = &collateral_withdrawn_delta.delta_value; // ??? 
+        let delta_root_identifier = substreams::key::segment_at(collateral_balance_delta.get_key(), 0);
 
+        if delta_root_identifier != "collateral_balance" {continue};
 
-      
+        let bid_id = substreams::key::segment_at(collateral_balance_delta.get_key(), 1);
+        let collateral_address = substreams::key::segment_at(collateral_balance_delta.get_key(), 2);
+        let collateral_type = substreams::key::segment_at(collateral_balance_delta.get_key(), 3);
+        let token_id = substreams::key::segment_at(collateral_balance_delta.get_key(), 4);
 
-         let string_store_key = format!("bid_originated_from_pool:{}", bid_id);
-         if let Some( group_pool_address ) = string_get_store.get_at(ord, string_store_key){
+        let delta_value = collateral_balance_delta.new_value.clone() - collateral_balance_delta.old_value.clone();
 
-            let output_store_key = format!("total_collateral_amount_withdrawn:{}", group_pool_address);
+        let string_store_key = format!("bid_originated_from_pool:{}", bid_id);
+        if let Some( group_pool_address ) = string_get_store.get_at(ord, string_store_key){
 
-            output_store.add(ord, &output_store_key, delta_value.clone() );
+            if is_fungible_collateral(collateral_type) {
+                let output_store_key = format!("net_collateral_escrowed:{}", group_pool_address);
 
+                output_store.add(ord, &output_store_key, delta_value.clone() );
+            }
 
-         }
-                    
+            let output_store_key = format!("pool_collateral_asset:{}:{}:{}:{}:net_escrowed", group_pool_address, collateral_address, collateral_type, token_id);
 
-      }
+            output_store.add(ord, &output_store_key, delta_value.clone() );
 
-     
-}
+            let output_store_key = format!("pool_collateral_asset_total:{}:{}:{}:net_escrowed", group_pool_address, collateral_address, collateral_type);
 
+            output_store.add(ord, &output_store_key, delta_value );
 
+        }
 
+    }
 
- 
- 
+}
 
 
 
@@ -1341,10 +2661,87 @@ fn store_bid_from_pool_data(
 
       
     });
+
+    // the pool's loans are originated through this TellerV2, used to pick up its events for those bids 
+    events.lendergroup_pool_initializeds.iter().for_each(|evt: &contract::LendergroupPoolInitialized| {
+
+        string_set_store.set(ord, format!("pool_teller_v2:{}", evt.evt_address ), &Hex(&evt.teller_v2_address).to_string() );
+
+    });
  
 
 }
 
+// cumulative repayments per lender group loan, the LoanRepaid totals are pool wide 
+#[substreams::handlers::store]
+fn store_group_loan_repayments(
+    events:  contract::Events, 
+
+    bigint_add_store: StoreAddBigInt
+) {
+
+    let ord = 0; // FOR NOW - CAN CAUSE ISSUES - GET FROM LOG AND STUFF INTO EVENT    
+
+    events.lendergroup_loan_repaids.iter().for_each(|evt: &contract::LendergroupLoanRepaid| {
+
+        bigint_add_store.add(ord, format!("group_loan:{}:total_principal_repaid", evt.bid_id), BigInt::from_str(&evt.principal_amount).unwrap_or(BigInt::zero()));
+        bigint_add_store.add(ord, format!("group_loan:{}:total_interest_repaid", evt.bid_id), BigInt::from_str(&evt.interest_amount).unwrap_or(BigInt::zero()));
+        bigint_add_store.add(ord, format!("group_loan:{}:repayment_count", evt.bid_id), BigInt::from(1));
+
+    });
+
+}
+
+
+/*
+
+    Tracks which lenders currently have shares prepared for withdrawal.
+    Ordinals are the evt index so graph_out can match a delete back to the EarningsWithdrawn that caused it.
+
+*/
+#[substreams::handlers::store]
+fn store_withdrawal_requests(
+    events:  contract::Events, 
+
+    bigint_set_store: StoreSetBigInt
+) {
+
+    enum WithdrawalQueueAction<'a> {
+        Prepared(&'a contract::LendergroupPoolSharesPrepared),
+        Withdrawn(&'a contract::LendergroupEarningsWithdrawn),
+    }
+
+    let mut actions: Vec<(u32, WithdrawalQueueAction)> = Vec::new();
+
+    events.lendergroup_pool_shares_prepareds.iter().for_each(|evt| {
+        actions.push((evt.evt_index, WithdrawalQueueAction::Prepared(evt)));
+    });
+
+    events.lendergroup_earnings_withdrawns.iter().for_each(|evt| {
+        actions.push((evt.evt_index, WithdrawalQueueAction::Withdrawn(evt)));
+    });
+
+    // a prepare and a withdraw can land in the same block, so apply them in log order 
+    actions.sort_by_key(|(evt_index, _)| *evt_index);
+
+    for (evt_index, action) in actions {
+
+        let ord = evt_index as u64;
+
+        match action {
+            WithdrawalQueueAction::Prepared(evt) => {
+                bigint_set_store.set(ord, format!("withdrawal_request:{}:{}", evt.evt_address, Hex(&evt.lender).to_string()), &BigInt::from(evt.prepared_at));
+            },
+            WithdrawalQueueAction::Withdrawn(evt) => {
+                bigint_set_store.delete_prefix(ord as i64, &format!("withdrawal_request:{}:{}", evt.evt_address, Hex(&evt.lender).to_string()));
+            },
+        }
+    }
+
+}
+
+
+
 /*
 
 
@@ -1369,6 +2766,7 @@ The block stream encountered a substreams fatal error and will not retry:
 #[substreams::handlers::store]
 fn store_lendergroup_user_metrics_deltas(
     events:  contract::Events, 
+    pool_shares_events: contract::Events,
     bigint_add_store: StoreAddBigInt,
  
 ) {
@@ -1387,8 +2785,32 @@ fn store_lendergroup_user_metrics_deltas(
         let user_store_key: String = format!("group_user_metric:{}:{}:total_principal_tokens_committed", evt.evt_address,Hex(&evt.lender).to_string());
         bigint_add_store.add(ord,&user_store_key, BigInt::from_str(&evt.amount).unwrap_or(BigInt::zero()));
 
-      // need to write a whole set of drivers to track   shares tokens !! 
-      // need ERC20 abi also 
+      // shares tokens are tracked from the pool shares token transfers below 
+    });
+
+    // mints, burns and secondary transfers between wallets all move the holder balance, but are not pool interactions 
+    pool_shares_events.lendergroup_pool_shares_transfers.iter().for_each(|evt: &contract::LendergroupPoolSharesTransfer| {
+
+        let amount = BigInt::from_str(&evt.amount).unwrap_or(BigInt::zero());
+
+        // a wallet to wallet transfer moves shares without their cost basis 
+        if evt.from != ZERO_ADDRESS && evt.to != ZERO_ADDRESS {
+            let user_store_key: String = format!("group_user_metric:{}:{}:secondary_share_transfer_count", evt.group_pool_address,Hex(&evt.from).to_string());
+            bigint_add_store.add(ord,&user_store_key, BigInt::from( 1 ));
+
+            let user_store_key: String = format!("group_user_metric:{}:{}:secondary_share_transfer_count", evt.group_pool_address,Hex(&evt.to).to_string());
+            bigint_add_store.add(ord,&user_store_key, BigInt::from( 1 ));
+        }
+
+        if evt.from != ZERO_ADDRESS {
+            let user_store_key: String = format!("group_user_metric:{}:{}:shares_tokens_balance", evt.group_pool_address,Hex(&evt.from).to_string());
+            bigint_add_store.add(ord,&user_store_key, BigInt::zero() - amount.clone());
+        }
+
+        if evt.to != ZERO_ADDRESS {
+            let user_store_key: String = format!("group_user_metric:{}:{}:shares_tokens_balance", evt.group_pool_address,Hex(&evt.to).to_string());
+            bigint_add_store.add(ord,&user_store_key, amount);
+        }
     });
 
     events.lendergroup_borrower_accepted_funds.iter().for_each(|evt: &contract::LendergroupBorrowerAcceptedFunds| {
@@ -1726,6 +3148,145 @@ fn store_lendergroup_pool_metrics(
 }
 
 
+fn tellerv2_bid_pool_address(
+    log: &eth::Log,
+    bid_id: &BigInt,
+    store_bids_from_pools_data: &StoreGetString,
+) -> Option<String> {
+
+    let group_pool_address = store_bids_from_pools_data.get_last(format!("bid_originated_from_pool:{}", bid_id));
+    let pool_teller_v2_address = group_pool_address.as_ref()
+        .and_then(|group_pool_address| store_bids_from_pools_data.get_last(format!("pool_teller_v2:{}", group_pool_address)));
+
+    match_tellerv2_bid_pool_address(&log.address, group_pool_address, pool_teller_v2_address)
+}
+
+// bid ids are only unique per TellerV2 deployment, so the log must come from the TellerV2 the pool lends through 
+fn match_tellerv2_bid_pool_address(
+    log_address: &[u8],
+    group_pool_address: Option<String>,
+    pool_teller_v2_address: Option<String>,
+) -> Option<String> {
+
+    let group_pool_address = group_pool_address?;
+
+    if pool_teller_v2_address? != Hex(log_address).to_string() {
+        return None;
+    }
+
+    Some(group_pool_address)
+}
+
+// TellerV2 events, only for bids that were originated by one of our lender group pools 
+#[substreams::handlers::map]
+fn map_tellerv2_events(
+    blk: eth::Block,
+    store_bids_from_pools_data: StoreGetString,
+) -> Result<contract::Events, substreams::errors::Error> {
+    let mut events = contract::Events::default();
+
+    events.tellerv2_accepted_bids.append(&mut blk
+        .receipts()
+        .flat_map(|view| {
+            view.receipt.logs.iter()
+                .filter_map(|log| {
+                    if let Some(event) = abi::tellerv2::events::AcceptedBid::match_and_decode(log) {
+                        let group_pool_address = tellerv2_bid_pool_address(log, &event.bid_id, &store_bids_from_pools_data)?;
+
+                        return Some(contract::Tellerv2AcceptedBid {
+                            evt_tx_hash: Hex(&view.transaction.hash).to_string(),
+                            evt_index: log.block_index,
+                            evt_block_time: blk.timestamp_seconds(),
+                            evt_block_number: blk.number,
+                            evt_address: Hex(&log.address).to_string(),
+                            group_pool_address,
+                            bid_id: event.bid_id.to_string(),
+                            lender: event.lender,
+                        });
+                    }
+
+                    None
+                })
+        })
+        .collect());
+
+    events.tellerv2_loan_repayments.append(&mut blk
+        .receipts()
+        .flat_map(|view| {
+            view.receipt.logs.iter()
+                .filter_map(|log| {
+                    if let Some(event) = abi::tellerv2::events::LoanRepayment::match_and_decode(log) {
+                        let group_pool_address = tellerv2_bid_pool_address(log, &event.bid_id, &store_bids_from_pools_data)?;
+
+                        return Some(contract::Tellerv2LoanRepayment {
+                            evt_tx_hash: Hex(&view.transaction.hash).to_string(),
+                            evt_index: log.block_index,
+                            evt_block_time: blk.timestamp_seconds(),
+                            evt_block_number: blk.number,
+                            evt_address: Hex(&log.address).to_string(),
+                            group_pool_address,
+                            bid_id: event.bid_id.to_string(),
+                        });
+                    }
+
+                    None
+                })
+        })
+        .collect());
+
+    events.tellerv2_loan_repaids.append(&mut blk
+        .receipts()
+        .flat_map(|view| {
+            view.receipt.logs.iter()
+                .filter_map(|log| {
+                    if let Some(event) = abi::tellerv2::events::LoanRepaid::match_and_decode(log) {
+                        let group_pool_address = tellerv2_bid_pool_address(log, &event.bid_id, &store_bids_from_pools_data)?;
+
+                        return Some(contract::Tellerv2LoanRepaid {
+                            evt_tx_hash: Hex(&view.transaction.hash).to_string(),
+                            evt_index: log.block_index,
+                            evt_block_time: blk.timestamp_seconds(),
+                            evt_block_number: blk.number,
+                            evt_address: Hex(&log.address).to_string(),
+                            group_pool_address,
+                            bid_id: event.bid_id.to_string(),
+                        });
+                    }
+
+                    None
+                })
+        })
+        .collect());
+
+    events.tellerv2_loan_liquidateds.append(&mut blk
+        .receipts()
+        .flat_map(|view| {
+            view.receipt.logs.iter()
+                .filter_map(|log| {
+                    if let Some(event) = abi::tellerv2::events::LoanLiquidated::match_and_decode(log) {
+                        let group_pool_address = tellerv2_bid_pool_address(log, &event.bid_id, &store_bids_from_pools_data)?;
+
+                        return Some(contract::Tellerv2LoanLiquidated {
+                            evt_tx_hash: Hex(&view.transaction.hash).to_string(),
+                            evt_index: log.block_index,
+                            evt_block_time: blk.timestamp_seconds(),
+                            evt_block_number: blk.number,
+                            evt_address: Hex(&log.address).to_string(),
+                            group_pool_address,
+                            bid_id: event.bid_id.to_string(),
+                            liquidator: event.liquidator,
+                        });
+                    }
+
+                    None
+                })
+        })
+        .collect());
+
+    Ok(events)
+}
+
+
 #[substreams::handlers::map]
 fn map_events(
     blk: eth::Block,
@@ -1751,12 +3312,38 @@ fn graph_out(
     deltas_lendergroup_user_metrics: Deltas<DeltaBigInt>,
 
     store_collateral_withdrawn_data: StoreGetBigInt, 
-  //  store_lendergroup_user_metrics: StoreGetBigInt, 
+    store_lendergroup_user_metrics: StoreGetBigInt, 
+    store_pool_share_holders: StoreGetString,
+    store_pool_share_holder_lists: StoreGetArray<String>,
+
+    deltas_pool_collateral_balance: Deltas<DeltaBigInt>,
+    store_pool_collateral_balance: StoreGetBigInt,
+    deltas_pool_collateral_token_ids: Deltas<DeltaString>,
+
+    collateral_events: collateral_contract::Events,
+    deltas_bid_collateral_amounts: Deltas<DeltaBigInt>,
+    store_bid_collateral_amounts: StoreGetBigInt,
+
+    deltas_withdrawal_requests: Deltas<DeltaBigInt>,
+
+    pool_shares_events: contract::Events,
+
+    deltas_group_loan_repayments: Deltas<DeltaBigInt>,
+
+    tellerv2_events: contract::Events,
 
 ) -> Result<EntityChanges, substreams::errors::Error> {
     // Initialize Database Changes container
     let mut tables = EntityChangesTables::new();
     graph_factory_out(&events, &mut tables);
+    graph_governance_out(&events, &mut tables);
+    graph_collateral_out(
+        &collateral_events,
+        &mut tables,
+        &deltas_bid_collateral_amounts,
+        &store_bid_collateral_amounts,
+        &store_bids_from_pools_data,
+        );
     graph_lendergroup_out(
         &events, 
         &mut tables, 
@@ -1768,8 +3355,22 @@ fn graph_out(
 
         &deltas_lendergroup_user_metrics,
 
-        &store_collateral_withdrawn_data
-      //  &store_lendergroup_user_metrics,
+        &store_collateral_withdrawn_data,
+        &store_lendergroup_user_metrics,
+        &store_pool_share_holders,
+        &store_pool_share_holder_lists,
+
+        &deltas_pool_collateral_balance,
+        &store_pool_collateral_balance,
+        &deltas_pool_collateral_token_ids,
+
+        &deltas_withdrawal_requests,
+
+        &pool_shares_events,
+
+        &deltas_group_loan_repayments,
+
+        &tellerv2_events,
         );
         
         
@@ -1795,4 +3396,138 @@ fn graph_out(
         
                 
     Ok(tables.to_entity_changes())
-    }
\ No newline at end of file
+    }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_group_user_metric_key() {
+        let key = "group_user_metric:5c0b0c7bde3eaeb0e3ca4f1e0bf12a6e1e1f7ae2:1a2b3c:shares_tokens_balance";
+
+        assert_eq!(
+            parse_group_user_metric_key(key),
+            Some(("5c0b0c7bde3eaeb0e3ca4f1e0bf12a6e1e1f7ae2", "1a2b3c", "shares_tokens_balance"))
+        );
+    }
+
+    #[test]
+    fn test_parse_group_user_metric_key_rejects_other_keys() {
+        assert_eq!(parse_group_user_metric_key("group_pool_metric:5c0b:total_principal_tokens_committed"), None);
+        assert_eq!(parse_group_user_metric_key("group_user_metric:5c0b:1a2b"), None);
+        assert_eq!(parse_group_user_metric_key("group_user_metric:5c0b:1a2b:shares_tokens_balance:extra"), None);
+    }
+
+    #[test]
+    fn test_parse_pool_collateral_asset_key() {
+        let parsed_key = parse_pool_collateral_asset_key("pool_collateral_asset:5c0b:bc4c:1:42:net_escrowed").unwrap();
+
+        assert_eq!(parsed_key.group_pool_address, "5c0b");
+        assert_eq!(parsed_key.collateral_address, "bc4c");
+        assert_eq!(parsed_key.collateral_type, "1");
+        assert_eq!(parsed_key.token_id, "42");
+        assert_eq!(parsed_key.metric, "net_escrowed");
+    }
+
+    #[test]
+    fn test_parse_pool_collateral_asset_key_rejects_other_keys() {
+        // asset totals and the pool wide total share the store, but not the key shape 
+        assert!(parse_pool_collateral_asset_key("pool_collateral_asset_total:5c0b:bc4c:1:net_escrowed").is_none());
+        assert!(parse_pool_collateral_asset_key("net_collateral_escrowed:5c0b").is_none());
+        assert!(parse_pool_collateral_asset_key("pool_collateral_asset:5c0b:bc4c:1:net_escrowed").is_none());
+    }
+
+    #[test]
+    fn test_is_fungible_collateral() {
+        assert!(is_fungible_collateral("0"));
+        assert!(!is_fungible_collateral("1")); // erc721 
+        assert!(!is_fungible_collateral("2")); // erc1155 
+    }
+
+    #[test]
+    fn test_shares_position_value() {
+        let shares_tokens_balance = BigInt::from(2_000_000);
+        let shares_exchange_rate = BigInt::from_str("1050000000000000000").unwrap(); // 1.05 
+        let exchange_rate_expansion_factor = BigInt::from_str("1000000000000000000").unwrap();
+
+        assert_eq!(
+            shares_position_value(&shares_tokens_balance, &shares_exchange_rate, &exchange_rate_expansion_factor),
+            BigInt::from(2_100_000)
+        );
+    }
+
+    #[test]
+    fn test_shares_position_value_without_expansion_factor() {
+        assert_eq!(
+            shares_position_value(&BigInt::from(2_000_000), &BigInt::from(1), &BigInt::zero()),
+            BigInt::zero()
+        );
+    }
+
+    #[test]
+    fn test_lender_interest_earned() {
+        let shares_balance_value = BigInt::from(600);
+        let total_principal_tokens_withdrawn = BigInt::from(500);
+        let total_principal_tokens_committed = BigInt::from(1_000);
+
+        assert_eq!(
+            lender_interest_earned(&shares_balance_value, &total_principal_tokens_withdrawn, &total_principal_tokens_committed, false),
+            Some(BigInt::from(100))
+        );
+    }
+
+    #[test]
+    fn test_lender_interest_earned_skips_transferred_shares() {
+        assert_eq!(
+            lender_interest_earned(&BigInt::from(600), &BigInt::zero(), &BigInt::zero(), true),
+            None
+        );
+    }
+
+    #[test]
+    fn test_match_tellerv2_bid_pool_address() {
+        let teller_v2_address = hex!("00182fdb0b880ee24d428e3cc39383717677c37e");
+
+        assert_eq!(
+            match_tellerv2_bid_pool_address(
+                &teller_v2_address,
+                Some("5c0b0c7bde3eaeb0e3ca4f1e0bf12a6e1e1f7ae2".to_string()),
+                Some("00182fdb0b880ee24d428e3cc39383717677c37e".to_string()),
+            ),
+            Some("5c0b0c7bde3eaeb0e3ca4f1e0bf12a6e1e1f7ae2".to_string())
+        );
+    }
+
+    #[test]
+    fn test_match_tellerv2_bid_pool_address_skips_bids_not_from_a_pool() {
+        let teller_v2_address = hex!("00182fdb0b880ee24d428e3cc39383717677c37e");
+
+        assert_eq!(match_tellerv2_bid_pool_address(&teller_v2_address, None, None), None);
+    }
+
+    #[test]
+    fn test_match_tellerv2_bid_pool_address_skips_other_tellerv2_deployments() {
+        // the same bid id on a TellerV2 the pool does not lend through 
+        let other_teller_v2_address = hex!("d3d79a066f2cd471841c047d372f218252dbf8ed");
+
+        assert_eq!(
+            match_tellerv2_bid_pool_address(
+                &other_teller_v2_address,
+                Some("5c0b0c7bde3eaeb0e3ca4f1e0bf12a6e1e1f7ae2".to_string()),
+                Some("00182fdb0b880ee24d428e3cc39383717677c37e".to_string()),
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_match_tellerv2_bid_pool_address_skips_pools_without_a_known_tellerv2() {
+        let teller_v2_address = hex!("00182fdb0b880ee24d428e3cc39383717677c37e");
+
+        assert_eq!(
+            match_tellerv2_bid_pool_address(&teller_v2_address, Some("5c0b0c7bde3eaeb0e3ca4f1e0bf12a6e1e1f7ae2".to_string()), None),
+            None
+        );
+    }
+}
@@ -8,6 +8,14 @@ pub struct Events {
     pub collateral_manager_collateral_deposited: ::prost::alloc::vec::Vec<CollateralmanagerCollateralDeposited>,
     #[prost(message, repeated, tag="3")]
     pub collateral_manager_collateral_withdrawn: ::prost::alloc::vec::Vec<CollateralmanagerCollateralWithdrawn>,
+    #[prost(message, repeated, tag="4")]
+    pub collateral_manager_collateral_committed: ::prost::alloc::vec::Vec<CollateralmanagerCollateralCommitted>,
+    #[prost(message, repeated, tag="5")]
+    pub collateral_manager_collateral_claimed: ::prost::alloc::vec::Vec<CollateralmanagerCollateralClaimed>,
+    #[prost(message, repeated, tag="6")]
+    pub collateral_escrow_collateral_deposited: ::prost::alloc::vec::Vec<CollateralescrowCollateralDeposited>,
+    #[prost(message, repeated, tag="7")]
+    pub collateral_escrow_collateral_withdrawn: ::prost::alloc::vec::Vec<CollateralescrowCollateralWithdrawn>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -71,4 +79,82 @@ pub struct CollateralmanagerCollateralWithdrawn {
     #[prost(bytes="vec", tag="10")]
     pub recipient: ::prost::alloc::vec::Vec<u8>,
 }
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CollateralmanagerCollateralCommitted {
+    #[prost(string, tag="1")]
+    pub evt_tx_hash: ::prost::alloc::string::String,
+    #[prost(uint32, tag="2")]
+    pub evt_index: u32,
+    #[prost(uint64, tag="3")]
+    pub evt_block_time: u64,
+    #[prost(uint64, tag="4")]
+    pub evt_block_number: u64,
+    #[prost(string, tag="5")]
+    pub bid_id: ::prost::alloc::string::String,
+    #[prost(uint32, tag="6")]
+    pub collateral_type: u32,
+    #[prost(bytes="vec", tag="7")]
+    pub collateral_address: ::prost::alloc::vec::Vec<u8>,
+    #[prost(string, tag="8")]
+    pub amount: ::prost::alloc::string::String,
+    #[prost(string, tag="9")]
+    pub token_id: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CollateralmanagerCollateralClaimed {
+    #[prost(string, tag="1")]
+    pub evt_tx_hash: ::prost::alloc::string::String,
+    #[prost(uint32, tag="2")]
+    pub evt_index: u32,
+    #[prost(uint64, tag="3")]
+    pub evt_block_time: u64,
+    #[prost(uint64, tag="4")]
+    pub evt_block_number: u64,
+    #[prost(string, tag="5")]
+    pub bid_id: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CollateralescrowCollateralDeposited {
+    #[prost(string, tag="1")]
+    pub evt_tx_hash: ::prost::alloc::string::String,
+    #[prost(uint32, tag="2")]
+    pub evt_index: u32,
+    #[prost(uint64, tag="3")]
+    pub evt_block_time: u64,
+    #[prost(uint64, tag="4")]
+    pub evt_block_number: u64,
+    #[prost(bytes="vec", tag="5")]
+    pub evt_address: ::prost::alloc::vec::Vec<u8>,
+    #[prost(string, tag="6")]
+    pub bid_id: ::prost::alloc::string::String,
+    #[prost(bytes="vec", tag="7")]
+    pub collateral_address: ::prost::alloc::vec::Vec<u8>,
+    #[prost(string, tag="8")]
+    pub amount: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CollateralescrowCollateralWithdrawn {
+    #[prost(string, tag="1")]
+    pub evt_tx_hash: ::prost::alloc::string::String,
+    #[prost(uint32, tag="2")]
+    pub evt_index: u32,
+    #[prost(uint64, tag="3")]
+    pub evt_block_time: u64,
+    #[prost(uint64, tag="4")]
+    pub evt_block_number: u64,
+    #[prost(bytes="vec", tag="5")]
+    pub evt_address: ::prost::alloc::vec::Vec<u8>,
+    #[prost(string, tag="6")]
+    pub bid_id: ::prost::alloc::string::String,
+    #[prost(bytes="vec", tag="7")]
+    pub collateral_address: ::prost::alloc::vec::Vec<u8>,
+    #[prost(string, tag="8")]
+    pub amount: ::prost::alloc::string::String,
+    #[prost(bytes="vec", tag="9")]
+    pub recipient: ::prost::alloc::vec::Vec<u8>,
+}
 // @@protoc_insertion_point(module)
@@ -30,6 +30,28 @@ pub struct Events {
     pub lendergroup_pool_initializeds: ::prost::alloc::vec::Vec<LendergroupPoolInitialized>,
     #[prost(message, repeated, tag="14")]
     pub lendergroup_unpauseds: ::prost::alloc::vec::Vec<LendergroupUnpaused>,
+    #[prost(message, repeated, tag="15")]
+    pub factory_ownership_transferreds: ::prost::alloc::vec::Vec<FactoryOwnershipTransferred>,
+    #[prost(message, repeated, tag="16")]
+    pub factory_initializeds: ::prost::alloc::vec::Vec<FactoryInitialized>,
+    #[prost(message, repeated, tag="17")]
+    pub lendergroup_pool_shares_prepareds: ::prost::alloc::vec::Vec<LendergroupPoolSharesPrepared>,
+    #[prost(message, repeated, tag="18")]
+    pub lendergroup_pool_shares_transfers: ::prost::alloc::vec::Vec<LendergroupPoolSharesTransfer>,
+    #[prost(message, repeated, tag="19")]
+    pub tellerv2_accepted_bids: ::prost::alloc::vec::Vec<Tellerv2AcceptedBid>,
+    #[prost(message, repeated, tag="20")]
+    pub tellerv2_loan_repayments: ::prost::alloc::vec::Vec<Tellerv2LoanRepayment>,
+    #[prost(message, repeated, tag="21")]
+    pub tellerv2_loan_repaids: ::prost::alloc::vec::Vec<Tellerv2LoanRepaid>,
+    #[prost(message, repeated, tag="22")]
+    pub tellerv2_loan_liquidateds: ::prost::alloc::vec::Vec<Tellerv2LoanLiquidated>,
+    #[prost(message, repeated, tag="23")]
+    pub lendergroup_admin_changeds: ::prost::alloc::vec::Vec<LendergroupAdminChanged>,
+    #[prost(message, repeated, tag="24")]
+    pub lendergroup_beacon_upgradeds: ::prost::alloc::vec::Vec<LendergroupBeaconUpgraded>,
+    #[prost(message, repeated, tag="25")]
+    pub lendergroup_upgradeds: ::prost::alloc::vec::Vec<LendergroupUpgraded>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -77,6 +99,36 @@ pub struct FactoryDeployedLenderGroupContract {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FactoryOwnershipTransferred {
+    #[prost(string, tag="1")]
+    pub evt_tx_hash: ::prost::alloc::string::String,
+    #[prost(uint32, tag="2")]
+    pub evt_index: u32,
+    #[prost(uint64, tag="3")]
+    pub evt_block_time: u64,
+    #[prost(uint64, tag="4")]
+    pub evt_block_number: u64,
+    #[prost(bytes="vec", tag="5")]
+    pub previous_owner: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes="vec", tag="6")]
+    pub new_owner: ::prost::alloc::vec::Vec<u8>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FactoryInitialized {
+    #[prost(string, tag="1")]
+    pub evt_tx_hash: ::prost::alloc::string::String,
+    #[prost(uint32, tag="2")]
+    pub evt_index: u32,
+    #[prost(uint64, tag="3")]
+    pub evt_block_time: u64,
+    #[prost(uint64, tag="4")]
+    pub evt_block_number: u64,
+    #[prost(uint64, tag="5")]
+    pub version: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct FactoryUpgraded {
     #[prost(string, tag="1")]
     pub evt_tx_hash: ::prost::alloc::string::String,
@@ -309,4 +361,172 @@ pub struct LendergroupUnpaused {
     #[prost(bytes="vec", tag="6")]
     pub account: ::prost::alloc::vec::Vec<u8>,
 }
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct LendergroupPoolSharesPrepared {
+    #[prost(string, tag="1")]
+    pub evt_tx_hash: ::prost::alloc::string::String,
+    #[prost(uint32, tag="2")]
+    pub evt_index: u32,
+    #[prost(uint64, tag="3")]
+    pub evt_block_time: u64,
+    #[prost(uint64, tag="4")]
+    pub evt_block_number: u64,
+    #[prost(string, tag="5")]
+    pub evt_address: ::prost::alloc::string::String,
+    #[prost(bytes="vec", tag="6")]
+    pub lender: ::prost::alloc::vec::Vec<u8>,
+    #[prost(string, tag="7")]
+    pub shares_amount: ::prost::alloc::string::String,
+    #[prost(uint64, tag="8")]
+    pub prepared_at: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct LendergroupPoolSharesTransfer {
+    #[prost(string, tag="1")]
+    pub evt_tx_hash: ::prost::alloc::string::String,
+    #[prost(uint32, tag="2")]
+    pub evt_index: u32,
+    #[prost(uint64, tag="3")]
+    pub evt_block_time: u64,
+    #[prost(uint64, tag="4")]
+    pub evt_block_number: u64,
+    #[prost(string, tag="5")]
+    pub evt_address: ::prost::alloc::string::String,
+    #[prost(string, tag="6")]
+    pub group_pool_address: ::prost::alloc::string::String,
+    #[prost(bytes="vec", tag="7")]
+    pub from: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes="vec", tag="8")]
+    pub to: ::prost::alloc::vec::Vec<u8>,
+    #[prost(string, tag="9")]
+    pub amount: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Tellerv2AcceptedBid {
+    #[prost(string, tag="1")]
+    pub evt_tx_hash: ::prost::alloc::string::String,
+    #[prost(uint32, tag="2")]
+    pub evt_index: u32,
+    #[prost(uint64, tag="3")]
+    pub evt_block_time: u64,
+    #[prost(uint64, tag="4")]
+    pub evt_block_number: u64,
+    #[prost(string, tag="5")]
+    pub evt_address: ::prost::alloc::string::String,
+    #[prost(string, tag="6")]
+    pub group_pool_address: ::prost::alloc::string::String,
+    #[prost(string, tag="7")]
+    pub bid_id: ::prost::alloc::string::String,
+    #[prost(bytes="vec", tag="8")]
+    pub lender: ::prost::alloc::vec::Vec<u8>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Tellerv2LoanRepayment {
+    #[prost(string, tag="1")]
+    pub evt_tx_hash: ::prost::alloc::string::String,
+    #[prost(uint32, tag="2")]
+    pub evt_index: u32,
+    #[prost(uint64, tag="3")]
+    pub evt_block_time: u64,
+    #[prost(uint64, tag="4")]
+    pub evt_block_number: u64,
+    #[prost(string, tag="5")]
+    pub evt_address: ::prost::alloc::string::String,
+    #[prost(string, tag="6")]
+    pub group_pool_address: ::prost::alloc::string::String,
+    #[prost(string, tag="7")]
+    pub bid_id: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Tellerv2LoanRepaid {
+    #[prost(string, tag="1")]
+    pub evt_tx_hash: ::prost::alloc::string::String,
+    #[prost(uint32, tag="2")]
+    pub evt_index: u32,
+    #[prost(uint64, tag="3")]
+    pub evt_block_time: u64,
+    #[prost(uint64, tag="4")]
+    pub evt_block_number: u64,
+    #[prost(string, tag="5")]
+    pub evt_address: ::prost::alloc::string::String,
+    #[prost(string, tag="6")]
+    pub group_pool_address: ::prost::alloc::string::String,
+    #[prost(string, tag="7")]
+    pub bid_id: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Tellerv2LoanLiquidated {
+    #[prost(string, tag="1")]
+    pub evt_tx_hash: ::prost::alloc::string::String,
+    #[prost(uint32, tag="2")]
+    pub evt_index: u32,
+    #[prost(uint64, tag="3")]
+    pub evt_block_time: u64,
+    #[prost(uint64, tag="4")]
+    pub evt_block_number: u64,
+    #[prost(string, tag="5")]
+    pub evt_address: ::prost::alloc::string::String,
+    #[prost(string, tag="6")]
+    pub group_pool_address: ::prost::alloc::string::String,
+    #[prost(string, tag="7")]
+    pub bid_id: ::prost::alloc::string::String,
+    #[prost(bytes="vec", tag="8")]
+    pub liquidator: ::prost::alloc::vec::Vec<u8>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct LendergroupAdminChanged {
+    #[prost(string, tag="1")]
+    pub evt_tx_hash: ::prost::alloc::string::String,
+    #[prost(uint32, tag="2")]
+    pub evt_index: u32,
+    #[prost(uint64, tag="3")]
+    pub evt_block_time: u64,
+    #[prost(uint64, tag="4")]
+    pub evt_block_number: u64,
+    #[prost(string, tag="5")]
+    pub evt_address: ::prost::alloc::string::String,
+    #[prost(bytes="vec", tag="6")]
+    pub previous_admin: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes="vec", tag="7")]
+    pub new_admin: ::prost::alloc::vec::Vec<u8>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct LendergroupBeaconUpgraded {
+    #[prost(string, tag="1")]
+    pub evt_tx_hash: ::prost::alloc::string::String,
+    #[prost(uint32, tag="2")]
+    pub evt_index: u32,
+    #[prost(uint64, tag="3")]
+    pub evt_block_time: u64,
+    #[prost(uint64, tag="4")]
+    pub evt_block_number: u64,
+    #[prost(string, tag="5")]
+    pub evt_address: ::prost::alloc::string::String,
+    #[prost(bytes="vec", tag="6")]
+    pub beacon: ::prost::alloc::vec::Vec<u8>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct LendergroupUpgraded {
+    #[prost(string, tag="1")]
+    pub evt_tx_hash: ::prost::alloc::string::String,
+    #[prost(uint32, tag="2")]
+    pub evt_index: u32,
+    #[prost(uint64, tag="3")]
+    pub evt_block_time: u64,
+    #[prost(uint64, tag="4")]
+    pub evt_block_number: u64,
+    #[prost(string, tag="5")]
+    pub evt_address: ::prost::alloc::string::String,
+    #[prost(bytes="vec", tag="6")]
+    pub implementation: ::prost::alloc::vec::Vec<u8>,
+}
 // @@protoc_insertion_point(module)
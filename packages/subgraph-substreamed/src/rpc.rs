@@ -7,6 +7,7 @@ use prost::Message;
 use substreams::log;
 use substreams::scalar::BigInt;
 use substreams::Hex;
+use std::str::FromStr;
 use substreams_ethereum::rpc::RpcBatch;
 
 
@@ -30,6 +31,41 @@ pub struct LenderGroupPoolInitializationDataFromRpc {
 }
 
 
+pub struct SharesExchangeRatesFromRpc {
+
+    pub shares_exchange_rate: BigInt,
+
+    pub shares_exchange_rate_inverse: BigInt,
+
+    pub exchange_rate_expansion_factor: BigInt 
+
+}
+
+
+pub struct PoolCapacityDataFromRpc {
+
+    pub pool_utilization_ratio: BigInt,
+
+    pub pool_total_estimated_value: BigInt,
+
+    pub principal_amount_available_to_borrow: BigInt,
+
+    pub total_principal_tokens_outstanding_in_active_loans: BigInt 
+
+}
+
+
+pub struct LoanDueDatesFromRpc {
+
+    pub next_due_date: BigInt,
+
+    pub default_timestamp: BigInt,
+
+    pub last_repaid_timestamp: BigInt 
+
+}
+
+
 //maybe this is broken ?
 pub fn fetch_lender_group_pool_initialization_data_from_rpc(pool_contract_address: &String) -> Option<LenderGroupPoolInitializationDataFromRpc> {
         
@@ -114,4 +150,121 @@ pub fn fetch_token_amount_difference_from_liquidations(pool_contract_address: &S
     return  token_amount_difference ; 
  
  
-}
\ No newline at end of file
+}
+
+
+
+pub fn fetch_withdrawl_delay_time_seconds_from_rpc(pool_contract_address: &String) -> Option<BigInt> {
+        
+    let pool_contract_address_decoded = Hex::decode(pool_contract_address).unwrap(); 
+        
+        let withdrawl_delay_time_seconds_function = abi::lendergroup_contract::functions::WithdrawlDelayTimeSeconds {    };
+        let  withdrawl_delay_time_seconds  = withdrawl_delay_time_seconds_function.call(
+            pool_contract_address_decoded.clone()
+         )  ;
+    
+    return  withdrawl_delay_time_seconds ; 
+ 
+}
+
+
+
+// principal value of shares is  shares * shares_exchange_rate / exchange_rate_expansion_factor 
+pub fn fetch_shares_exchange_rates_from_rpc(pool_contract_address: &String) -> Option<SharesExchangeRatesFromRpc> {
+        
+    let pool_contract_address_decoded = Hex::decode(pool_contract_address).unwrap(); 
+        
+    let shares_exchange_rate_function = abi::lendergroup_contract::functions::SharesExchangeRate {};
+    let Some(shares_exchange_rate) = shares_exchange_rate_function.call(
+        pool_contract_address_decoded.clone()
+     ) else {return None};
+
+    let shares_exchange_rate_inverse_function = abi::lendergroup_contract::functions::SharesExchangeRateInverse {};
+    let Some(shares_exchange_rate_inverse) = shares_exchange_rate_inverse_function.call(
+        pool_contract_address_decoded.clone()
+     ) else {return None};
+
+    let exchange_rate_expansion_factor_function = abi::lendergroup_contract::functions::ExchangeRateExpansionFactor {};
+    let Some(exchange_rate_expansion_factor) = exchange_rate_expansion_factor_function.call(
+        pool_contract_address_decoded.clone()
+     ) else {return None};
+    
+    return Some(  
+        SharesExchangeRatesFromRpc{ 
+            shares_exchange_rate,
+            shares_exchange_rate_inverse,
+            exchange_rate_expansion_factor,
+        }
+    ); 
+ 
+}
+
+
+
+pub fn fetch_pool_capacity_data_from_rpc(pool_contract_address: &String) -> Option<PoolCapacityDataFromRpc> {
+        
+    let pool_contract_address_decoded = Hex::decode(pool_contract_address).unwrap(); 
+        
+    let pool_utilization_ratio_function = abi::lendergroup_contract::functions::GetPoolUtilizationRatio { active_loans_amount_delta: BigInt::zero() };
+    let Some(pool_utilization_ratio) = pool_utilization_ratio_function.call(
+        pool_contract_address_decoded.clone()
+     ) else {return None};
+
+    let pool_total_estimated_value_function = abi::lendergroup_contract::functions::GetPoolTotalEstimatedValue {};
+    let Some(pool_total_estimated_value) = pool_total_estimated_value_function.call(
+        pool_contract_address_decoded.clone()
+     ) else {return None};
+
+    let principal_amount_available_to_borrow_function = abi::lendergroup_contract::functions::GetPrincipalAmountAvailableToBorrow {};
+    let Some(principal_amount_available_to_borrow) = principal_amount_available_to_borrow_function.call(
+        pool_contract_address_decoded.clone()
+     ) else {return None};
+
+    let total_principal_tokens_outstanding_function = abi::lendergroup_contract::functions::GetTotalPrincipalTokensOutstandingInActiveLoans {};
+    let Some(total_principal_tokens_outstanding_in_active_loans) = total_principal_tokens_outstanding_function.call(
+        pool_contract_address_decoded.clone()
+     ) else {return None};
+    
+    return Some(  
+        PoolCapacityDataFromRpc{ 
+            pool_utilization_ratio,
+            pool_total_estimated_value,
+            principal_amount_available_to_borrow,
+            total_principal_tokens_outstanding_in_active_loans,
+        }
+    ); 
+ 
+}
+
+
+
+pub fn fetch_loan_due_dates_from_rpc(teller_v2_address: &String, bid_id: &String) -> Option<LoanDueDatesFromRpc> {
+        
+    let teller_v2_address_decoded = Hex::decode(teller_v2_address).unwrap(); 
+
+    let Ok(u_bid_id) = BigInt::from_str(bid_id) else {return None};
+        
+    let next_due_date_function = abi::tellerv2::functions::CalculateNextDueDate { u_bid_id: u_bid_id.clone() };
+    let Some(next_due_date) = next_due_date_function.call(
+        teller_v2_address_decoded.clone()
+     ) else {return None};
+
+    let default_timestamp_function = abi::tellerv2::functions::GetLoanDefaultTimestamp { u_bid_id: u_bid_id.clone() };
+    let Some(default_timestamp) = default_timestamp_function.call(
+        teller_v2_address_decoded.clone()
+     ) else {return None};
+
+    let last_repaid_timestamp_function = abi::tellerv2::functions::LastRepaidTimestamp { u_bid_id: u_bid_id.clone() };
+    let Some(last_repaid_timestamp) = last_repaid_timestamp_function.call(
+        teller_v2_address_decoded.clone()
+     ) else {return None};
+    
+    return Some(  
+        LoanDueDatesFromRpc{ 
+            next_due_date,
+            default_timestamp,
+            last_repaid_timestamp,
+        }
+    ); 
+ 
+}
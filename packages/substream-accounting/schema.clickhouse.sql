@@ -1,143 +1,184 @@
 CREATE TABLE IF NOT EXISTS tellerv2_accepted_bid (
     "evt_tx_hash" VARCHAR(64),
-    "evt_index" INT,
-    "evt_block_time" TIMESTAMP,
+    "evt_index" UInt32,
+    "evt_block_time" DateTime,
     "evt_block_number" UInt64,
     "bid_id" UInt256,
-    "lender" VARCHAR(40)
-) ENGINE = MergeTree PRIMARY KEY ("evt_tx_hash","evt_index");
+    "lender" VARCHAR(40),
+    "inside_pause_window" Bool,
+    "pause_window" Nullable(String)
+) ENGINE = ReplacingMergeTree ORDER BY ("evt_block_number","evt_tx_hash","evt_index");
 CREATE TABLE IF NOT EXISTS tellerv2_admin_changed (
     "evt_tx_hash" VARCHAR(64),
-    "evt_index" INT,
-    "evt_block_time" TIMESTAMP,
+    "evt_index" UInt32,
+    "evt_block_time" DateTime,
     "evt_block_number" UInt64,
     "new_admin" VARCHAR(40),
     "previous_admin" VARCHAR(40)
-) ENGINE = MergeTree PRIMARY KEY ("evt_tx_hash","evt_index");
+) ENGINE = ReplacingMergeTree ORDER BY ("evt_block_number","evt_tx_hash","evt_index");
 CREATE TABLE IF NOT EXISTS tellerv2_cancelled_bid (
     "evt_tx_hash" VARCHAR(64),
-    "evt_index" INT,
-    "evt_block_time" TIMESTAMP,
+    "evt_index" UInt32,
+    "evt_block_time" DateTime,
     "evt_block_number" UInt64,
     "bid_id" UInt256
-) ENGINE = MergeTree PRIMARY KEY ("evt_tx_hash","evt_index");
+) ENGINE = ReplacingMergeTree ORDER BY ("evt_block_number","evt_tx_hash","evt_index");
 CREATE TABLE IF NOT EXISTS tellerv2_fee_paid (
     "evt_tx_hash" VARCHAR(64),
-    "evt_index" INT,
-    "evt_block_time" TIMESTAMP,
+    "evt_index" UInt32,
+    "evt_block_time" DateTime,
     "evt_block_number" UInt64,
     "amount" UInt256,
     "bid_id" UInt256,
     "fee_type" TEXT
-) ENGINE = MergeTree PRIMARY KEY ("evt_tx_hash","evt_index");
+) ENGINE = ReplacingMergeTree ORDER BY ("evt_block_number","evt_tx_hash","evt_index");
 CREATE TABLE IF NOT EXISTS tellerv2_initialized (
     "evt_tx_hash" VARCHAR(64),
-    "evt_index" INT,
-    "evt_block_time" TIMESTAMP,
+    "evt_index" UInt32,
+    "evt_block_time" DateTime,
     "evt_block_number" UInt64,
     "version" UInt8
-) ENGINE = MergeTree PRIMARY KEY ("evt_tx_hash","evt_index");
+) ENGINE = ReplacingMergeTree ORDER BY ("evt_block_number","evt_tx_hash","evt_index");
 CREATE TABLE IF NOT EXISTS tellerv2_loan_liquidated (
     "evt_tx_hash" VARCHAR(64),
-    "evt_index" INT,
-    "evt_block_time" TIMESTAMP,
+    "evt_index" UInt32,
+    "evt_block_time" DateTime,
     "evt_block_number" UInt64,
     "bid_id" UInt256,
     "liquidator" VARCHAR(40)
-) ENGINE = MergeTree PRIMARY KEY ("evt_tx_hash","evt_index");
+) ENGINE = ReplacingMergeTree ORDER BY ("evt_block_number","evt_tx_hash","evt_index");
 CREATE TABLE IF NOT EXISTS tellerv2_loan_repaid (
     "evt_tx_hash" VARCHAR(64),
-    "evt_index" INT,
-    "evt_block_time" TIMESTAMP,
+    "evt_index" UInt32,
+    "evt_block_time" DateTime,
     "evt_block_number" UInt64,
     "bid_id" UInt256
-) ENGINE = MergeTree PRIMARY KEY ("evt_tx_hash","evt_index");
+) ENGINE = ReplacingMergeTree ORDER BY ("evt_block_number","evt_tx_hash","evt_index");
 CREATE TABLE IF NOT EXISTS tellerv2_loan_repayment (
     "evt_tx_hash" VARCHAR(64),
-    "evt_index" INT,
-    "evt_block_time" TIMESTAMP,
+    "evt_index" UInt32,
+    "evt_block_time" DateTime,
     "evt_block_number" UInt64,
     "bid_id" UInt256
-) ENGINE = MergeTree PRIMARY KEY ("evt_tx_hash","evt_index");
+) ENGINE = ReplacingMergeTree ORDER BY ("evt_block_number","evt_tx_hash","evt_index");
 CREATE TABLE IF NOT EXISTS tellerv2_market_forwarder_approved (
     "evt_tx_hash" VARCHAR(64),
-    "evt_index" INT,
-    "evt_block_time" TIMESTAMP,
+    "evt_index" UInt32,
+    "evt_block_time" DateTime,
     "evt_block_number" UInt64,
     "forwarder" VARCHAR(40),
     "market_id" UInt256,
     "sender" VARCHAR(40)
-) ENGINE = MergeTree PRIMARY KEY ("evt_tx_hash","evt_index");
+) ENGINE = ReplacingMergeTree ORDER BY ("evt_block_number","evt_tx_hash","evt_index");
 CREATE TABLE IF NOT EXISTS tellerv2_market_forwarder_renounced (
     "evt_tx_hash" VARCHAR(64),
-    "evt_index" INT,
-    "evt_block_time" TIMESTAMP,
+    "evt_index" UInt32,
+    "evt_block_time" DateTime,
     "evt_block_number" UInt64,
     "forwarder" VARCHAR(40),
     "market_id" UInt256,
     "sender" VARCHAR(40)
-) ENGINE = MergeTree PRIMARY KEY ("evt_tx_hash","evt_index");
+) ENGINE = ReplacingMergeTree ORDER BY ("evt_block_number","evt_tx_hash","evt_index");
 CREATE TABLE IF NOT EXISTS tellerv2_market_owner_cancelled_bid (
     "evt_tx_hash" VARCHAR(64),
-    "evt_index" INT,
-    "evt_block_time" TIMESTAMP,
+    "evt_index" UInt32,
+    "evt_block_time" DateTime,
     "evt_block_number" UInt64,
     "bid_id" UInt256
-) ENGINE = MergeTree PRIMARY KEY ("evt_tx_hash","evt_index");
+) ENGINE = ReplacingMergeTree ORDER BY ("evt_block_number","evt_tx_hash","evt_index");
 CREATE TABLE IF NOT EXISTS tellerv2_ownership_transferred (
     "evt_tx_hash" VARCHAR(64),
-    "evt_index" INT,
-    "evt_block_time" TIMESTAMP,
+    "evt_index" UInt32,
+    "evt_block_time" DateTime,
     "evt_block_number" UInt64,
     "new_owner" VARCHAR(40),
     "previous_owner" VARCHAR(40)
-) ENGINE = MergeTree PRIMARY KEY ("evt_tx_hash","evt_index");
+) ENGINE = ReplacingMergeTree ORDER BY ("evt_block_number","evt_tx_hash","evt_index");
 CREATE TABLE IF NOT EXISTS tellerv2_paused (
     "evt_tx_hash" VARCHAR(64),
-    "evt_index" INT,
-    "evt_block_time" TIMESTAMP,
+    "evt_index" UInt32,
+    "evt_block_time" DateTime,
     "evt_block_number" UInt64,
     "account" VARCHAR(40)
-) ENGINE = MergeTree PRIMARY KEY ("evt_tx_hash","evt_index");
+) ENGINE = ReplacingMergeTree ORDER BY ("evt_block_number","evt_tx_hash","evt_index");
 CREATE TABLE IF NOT EXISTS tellerv2_protocol_fee_set (
     "evt_tx_hash" VARCHAR(64),
-    "evt_index" INT,
-    "evt_block_time" TIMESTAMP,
+    "evt_index" UInt32,
+    "evt_block_time" DateTime,
     "evt_block_number" UInt64,
     "new_fee" UInt16,
     "old_fee" UInt16
-) ENGINE = MergeTree PRIMARY KEY ("evt_tx_hash","evt_index");
+) ENGINE = ReplacingMergeTree ORDER BY ("evt_block_number","evt_tx_hash","evt_index");
 CREATE TABLE IF NOT EXISTS tellerv2_submitted_bid (
     "evt_tx_hash" VARCHAR(64),
-    "evt_index" INT,
-    "evt_block_time" TIMESTAMP,
+    "evt_index" UInt32,
+    "evt_block_time" DateTime,
     "evt_block_number" UInt64,
     "bid_id" UInt256,
     "borrower" VARCHAR(40),
     "metadata_uri" TEXT,
-    "receiver" VARCHAR(40)
-) ENGINE = MergeTree PRIMARY KEY ("evt_tx_hash","evt_index");
+    "metadata_uri_text" Nullable(String),
+    "metadata_scheme" LowCardinality(String),
+    "receiver" VARCHAR(40),
+    "apr_bps" Nullable(UInt16),
+    "duration_seconds" Nullable(UInt32),
+    "collateral_count" UInt32,
+    "inside_pause_window" Bool,
+    "pause_window" Nullable(String)
+) ENGINE = ReplacingMergeTree ORDER BY ("evt_block_number","evt_tx_hash","evt_index");
 CREATE TABLE IF NOT EXISTS tellerv2_trusted_market_forwarder_set (
     "evt_tx_hash" VARCHAR(64),
-    "evt_index" INT,
-    "evt_block_time" TIMESTAMP,
+    "evt_index" UInt32,
+    "evt_block_time" DateTime,
     "evt_block_number" UInt64,
     "forwarder" VARCHAR(40),
     "market_id" UInt256,
     "sender" VARCHAR(40)
-) ENGINE = MergeTree PRIMARY KEY ("evt_tx_hash","evt_index");
+) ENGINE = ReplacingMergeTree ORDER BY ("evt_block_number","evt_tx_hash","evt_index");
 CREATE TABLE IF NOT EXISTS tellerv2_unpaused (
     "evt_tx_hash" VARCHAR(64),
-    "evt_index" INT,
-    "evt_block_time" TIMESTAMP,
+    "evt_index" UInt32,
+    "evt_block_time" DateTime,
     "evt_block_number" UInt64,
     "account" VARCHAR(40)
-) ENGINE = MergeTree PRIMARY KEY ("evt_tx_hash","evt_index");
+) ENGINE = ReplacingMergeTree ORDER BY ("evt_block_number","evt_tx_hash","evt_index");
 CREATE TABLE IF NOT EXISTS tellerv2_upgraded (
     "evt_tx_hash" VARCHAR(64),
-    "evt_index" INT,
-    "evt_block_time" TIMESTAMP,
+    "evt_index" UInt32,
+    "evt_block_time" DateTime,
     "evt_block_number" UInt64,
     "implementation" VARCHAR(40)
-) ENGINE = MergeTree PRIMARY KEY ("evt_tx_hash","evt_index");
+) ENGINE = ReplacingMergeTree ORDER BY ("evt_block_number","evt_tx_hash","evt_index");
 
+CREATE TABLE IF NOT EXISTS tellerv2_bid (
+    "bid_id" UInt256,
+    "updated_block_number" UInt64,
+    "updated_at" DateTime,
+    "status" LowCardinality(String),
+    "borrower" VARCHAR(40),
+    "lender" VARCHAR(40),
+    "market_id" UInt256,
+    "principal_token_address" VARCHAR(40),
+    "principal_amount" UInt256,
+    "principal_amount_usdc" Float64,
+    "total_repaid_principal" UInt256,
+    "total_repaid_interest" UInt256,
+    "apr_bps" UInt16,
+    "accepted_timestamp" UInt32,
+    "last_repaid_timestamp" UInt32
+) ENGINE = ReplacingMergeTree("updated_block_number") ORDER BY ("bid_id");
+CREATE TABLE IF NOT EXISTS tellerv2_bid_collateral (
+    "bid_id" UInt256,
+    "collateral_index" UInt32,
+    "collateral_type" UInt8,
+    "collateral_address" VARCHAR(40),
+    "amount" UInt256,
+    "token_id" UInt256
+) ENGINE = ReplacingMergeTree ORDER BY ("bid_id","collateral_index");
+CREATE TABLE IF NOT EXISTS token_price (
+    "base_token_address" VARCHAR(42),
+    "block_number" UInt64,
+    "block_time" DateTime,
+    "reference_token_address" VARCHAR(42),
+    "price_ratio" Float64
+) ENGINE = ReplacingMergeTree ORDER BY ("base_token_address","block_number");
@@ -5,9 +5,19 @@ CREATE TABLE IF NOT EXISTS tellerv2_accepted_bid (
     "evt_block_number" DECIMAL,
     "bid_id" DECIMAL,
     "lender" VARCHAR(40),
+    "inside_pause_window" BOOLEAN,
+    "pause_window" TEXT,
+    PRIMARY KEY(evt_tx_hash,evt_index)
+);
+CREATE TABLE IF NOT EXISTS tellerv2_admin_changed (
+    "evt_tx_hash" VARCHAR(64),
+    "evt_index" INT,
+    "evt_block_time" TIMESTAMP,
+    "evt_block_number" DECIMAL,
+    "new_admin" VARCHAR(40),
+    "previous_admin" VARCHAR(40),
     PRIMARY KEY(evt_tx_hash,evt_index)
 );
- 
 CREATE TABLE IF NOT EXISTS tellerv2_cancelled_bid (
     "evt_tx_hash" VARCHAR(64),
     "evt_index" INT,
@@ -121,7 +131,11 @@ CREATE TABLE IF NOT EXISTS tellerv2_submitted_bid (
     "bid_id" DECIMAL,
     "borrower" VARCHAR(40),
     "metadata_uri" TEXT,
+    "metadata_uri_text" TEXT,
+    "metadata_scheme" TEXT,
     "receiver" VARCHAR(40),
+    "inside_pause_window" BOOLEAN,
+    "pause_window" TEXT,
     PRIMARY KEY(evt_tx_hash,evt_index)
 );
 CREATE TABLE IF NOT EXISTS tellerv2_trusted_market_forwarder_set (
@@ -142,9 +156,7 @@ CREATE TABLE IF NOT EXISTS tellerv2_unpaused (
     "account" VARCHAR(40),
     PRIMARY KEY(evt_tx_hash,evt_index)
 );
- 
-
-CREATE TABLE IF NOT EXISTS tellerv2_bid (
+CREATE TABLE IF NOT EXISTS tellerv2_upgraded (
     "evt_tx_hash" VARCHAR(64),
     "evt_index" INT,
     "evt_block_time" TIMESTAMP,
@@ -153,3 +165,204 @@ CREATE TABLE IF NOT EXISTS tellerv2_bid (
     PRIMARY KEY(evt_tx_hash,evt_index)
 );
 
+CREATE TABLE IF NOT EXISTS tellerv2_bid (
+    "bid_id" DECIMAL,
+    "borrower" VARCHAR(40),
+    "receiver" VARCHAR(40),
+    "status" TEXT,
+    "accepted_at" TIMESTAMP,
+    "expiration_time" TIMESTAMP,
+    "apr_bps" DECIMAL,
+    "duration_seconds" DECIMAL,
+    "collateral_count" INT,
+    "lender" VARCHAR(40),
+    "principal_token_address" VARCHAR(40),
+    "principal_amount" DECIMAL,
+    "principal_amount_usdc" DECIMAL,
+    "accrued_interest" DECIMAL,
+    PRIMARY KEY(bid_id)
+);
+CREATE TABLE IF NOT EXISTS tellerv2_bid_collateral (
+    "bid_id" DECIMAL,
+    "collateral_index" INT,
+    "collateral_type" INT,
+    "collateral_address" VARCHAR(40),
+    "amount" DECIMAL,
+    "token_id" DECIMAL,
+    PRIMARY KEY(bid_id,collateral_index)
+);
+CREATE TABLE IF NOT EXISTS token_price (
+    "base_token_address" VARCHAR(42),
+    "reference_token_address" VARCHAR(42),
+    "price_ratio" TEXT,
+    PRIMARY KEY(base_token_address)
+);
+CREATE TABLE IF NOT EXISTS pause_window (
+    "id" TEXT,
+    "started_at" TIMESTAMP,
+    "started_block_number" DECIMAL,
+    "paused_by" VARCHAR(40),
+    "ended_at" TIMESTAMP,
+    "ended_block_number" DECIMAL,
+    "unpaused_by" VARCHAR(40),
+    "duration_seconds" DECIMAL,
+    "is_open" BOOLEAN,
+    PRIMARY KEY(id)
+);
+CREATE TABLE IF NOT EXISTS market_forwarder (
+    "market_id" DECIMAL,
+    "trusted_forwarder" VARCHAR(40),
+    "set_by" VARCHAR(40),
+    "updated_at" TIMESTAMP,
+    "updated_block_number" DECIMAL,
+    PRIMARY KEY(market_id)
+);
+CREATE TABLE IF NOT EXISTS forwarder_approval (
+    "market_id" DECIMAL,
+    "forwarder" VARCHAR(40),
+    "sender" VARCHAR(40),
+    "is_active" BOOLEAN,
+    "status_changed_at" TIMESTAMP,
+    "status_changed_block_number" DECIMAL,
+    PRIMARY KEY(market_id,forwarder,sender)
+);
+CREATE TABLE IF NOT EXISTS token_day_data (
+    "token_address" VARCHAR(42),
+    "day_index" DECIMAL,
+    "block_number" DECIMAL,
+    "block_time" TIMESTAMP,
+    "outstanding_principal" DECIMAL,
+    "originated_principal" DECIMAL,
+    "repaid_principal" DECIMAL,
+    "liquidated_principal" DECIMAL,
+    "total_originated_principal" DECIMAL,
+    "total_repaid_principal" DECIMAL,
+    "total_liquidated_principal" DECIMAL,
+    "outstanding_principal_usdc" DECIMAL,
+    "originated_principal_usdc" DECIMAL,
+    "repaid_principal_usdc" DECIMAL,
+    "liquidated_principal_usdc" DECIMAL,
+    PRIMARY KEY(token_address,day_index)
+);
+CREATE TABLE IF NOT EXISTS protocol_day_data (
+    "day_index" DECIMAL,
+    "block_number" DECIMAL,
+    "block_time" TIMESTAMP,
+    "outstanding_principal_usdc" DECIMAL,
+    "originated_principal_usdc" DECIMAL,
+    "repaid_principal_usdc" DECIMAL,
+    "liquidated_principal_usdc" DECIMAL,
+    "total_originated_principal_usdc" DECIMAL,
+    "total_repaid_principal_usdc" DECIMAL,
+    "total_liquidated_principal_usdc" DECIMAL,
+    PRIMARY KEY(day_index)
+);
+CREATE TABLE IF NOT EXISTS loan_interest_accrual (
+    "bid_id" DECIMAL,
+    "day_index" DECIMAL,
+    "accrued_at" TIMESTAMP,
+    "owed_principal" DECIMAL,
+    "apr_bps" DECIMAL,
+    "period_interest" DECIMAL,
+    "accrued_interest" DECIMAL,
+    PRIMARY KEY(bid_id,day_index)
+);
+CREATE TABLE IF NOT EXISTS borrower_credit_profile (
+    "borrower" VARCHAR(42),
+    "loans_accepted" DECIMAL,
+    "loans_repaid_on_time" DECIMAL,
+    "loans_repaid_late" DECIMAL,
+    "loans_defaulted" DECIMAL,
+    "loans_liquidated" DECIMAL,
+    "total_borrowed_usdc" DECIMAL,
+    "longest_delinquency_seconds" DECIMAL,
+    PRIMARY KEY(borrower)
+);
+CREATE TABLE IF NOT EXISTS vintage_month (
+    "market_id" DECIMAL,
+    "principal_token_address" VARCHAR(42),
+    "vintage" VARCHAR(7),
+    "months_since_origination" DECIMAL,
+    "block_number" DECIMAL,
+    "block_time" TIMESTAMP,
+    "loans_originated" DECIMAL,
+    "principal_originated" DECIMAL,
+    "principal_repaid" DECIMAL,
+    "interest_repaid" DECIMAL,
+    "loans_defaulted" DECIMAL,
+    "loans_liquidated" DECIMAL,
+    "principal_repaid_by_liquidator" DECIMAL,
+    PRIMARY KEY(market_id,principal_token_address,vintage,months_since_origination)
+);
+CREATE TABLE IF NOT EXISTS governance_action (
+    "evt_tx_hash" VARCHAR(64),
+    "evt_index" INT,
+    "evt_block_time" TIMESTAMP,
+    "evt_block_number" DECIMAL,
+    "contract_type" TEXT,
+    "contract_address" VARCHAR(42),
+    "action" TEXT,
+    "previous_value" TEXT,
+    "new_value" TEXT,
+    "account" VARCHAR(42),
+    PRIMARY KEY(evt_tx_hash,evt_index)
+);
+CREATE TABLE IF NOT EXISTS volume_filled_snapshot (
+    "evt_tx_hash" VARCHAR(64),
+    "evt_index" INT,
+    "evt_block_time" TIMESTAMP,
+    "evt_block_number" DECIMAL,
+    "bid_id" DECIMAL,
+    "lender" VARCHAR(40),
+    "principal_token_address" VARCHAR(40),
+    "lender_volume_filled" DECIMAL,
+    "total_volume_filled" DECIMAL,
+    "legacy_lender_volume_filled" DECIMAL,
+    "legacy_total_volume_filled" DECIMAL,
+    "indexed_lender_volume_filled" DECIMAL,
+    "indexed_total_volume_filled" DECIMAL,
+    "volume_mismatch" BOOLEAN,
+    PRIMARY KEY(evt_tx_hash,evt_index)
+);
+CREATE TABLE IF NOT EXISTS activity_hour_window (
+    "window_index" DECIMAL,
+    "period_start_unix" DECIMAL,
+    "period_end_unix" DECIMAL,
+    "open_block_number" DECIMAL,
+    "open_block_time" TIMESTAMP,
+    "closed" BOOLEAN,
+    "close_block_number" DECIMAL,
+    "close_block_time" TIMESTAMP,
+    "submitted_bid_count" DECIMAL,
+    "accepted_bid_count" DECIMAL,
+    "repayment_count" DECIMAL,
+    "liquidation_count" DECIMAL,
+    "fee_payment_count" DECIMAL,
+    "originated_principal_usdc" DECIMAL,
+    "repaid_principal_usdc" DECIMAL,
+    "repaid_interest_usdc" DECIMAL,
+    "liquidated_principal_usdc" DECIMAL,
+    "fees_usdc" DECIMAL,
+    PRIMARY KEY(window_index)
+);
+CREATE TABLE IF NOT EXISTS activity_day_window (
+    "window_index" DECIMAL,
+    "period_start_unix" DECIMAL,
+    "period_end_unix" DECIMAL,
+    "open_block_number" DECIMAL,
+    "open_block_time" TIMESTAMP,
+    "closed" BOOLEAN,
+    "close_block_number" DECIMAL,
+    "close_block_time" TIMESTAMP,
+    "submitted_bid_count" DECIMAL,
+    "accepted_bid_count" DECIMAL,
+    "repayment_count" DECIMAL,
+    "liquidation_count" DECIMAL,
+    "fee_payment_count" DECIMAL,
+    "originated_principal_usdc" DECIMAL,
+    "repaid_principal_usdc" DECIMAL,
+    "repaid_interest_usdc" DECIMAL,
+    "liquidated_principal_usdc" DECIMAL,
+    "fees_usdc" DECIMAL,
+    PRIMARY KEY(window_index)
+);
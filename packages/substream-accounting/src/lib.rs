@@ -4,6 +4,9 @@ mod rpc;
 use ethabi::{ethereum_types::H160, Address};
 use hex_literal::hex;
 use pb::contract::v1 as contract;
+use pb::sf::substreams::index::v1::Keys;
+use pb::teller::v1 as teller;
+use substreams::pb::substreams::Clock;
 use substreams::Hex;
 use substreams_database_change::pb::database::DatabaseChanges;
 use substreams_database_change::tables::Tables as DatabaseChangeTables;
@@ -14,6 +17,7 @@ use substreams_ethereum::Event;
 
 #[allow(unused_imports)]
 use num_traits::cast::ToPrimitive;
+use std::collections::{BTreeMap, BTreeSet};
 use std::str::FromStr;
 use substreams::scalar::{BigDecimal, BigInt};
 
@@ -55,6 +59,34 @@ fn map_tellerv2_events(blk: &eth::Block, events: &mut contract::Events) {
             .collect(),
     );
 
+    events.tellerv2_admin_changeds.append(
+        &mut blk
+            .receipts()
+            .flat_map(|view| {
+                view.receipt
+                    .logs
+                    .iter()
+                    .filter(|log| log.address == TELLERV2_TRACKED_CONTRACT)
+                    .filter_map(|log| {
+                        if let Some(event) =
+                            abi::tellerv2_contract::events::AdminChanged::match_and_decode(log)
+                        {
+                            return Some(contract::Tellerv2AdminChanged {
+                                evt_tx_hash: Hex(&view.transaction.hash).to_string(),
+                                evt_index: log.block_index,
+                                evt_block_time: Some(blk.timestamp().to_owned()),
+                                evt_block_number: blk.number,
+                                new_admin: event.new_admin,
+                                previous_admin: event.previous_admin,
+                            });
+                        }
+
+                        None
+                    })
+            })
+            .collect(),
+    );
+
     events.tellerv2_cancelled_bids.append(
         &mut blk
             .receipts()
@@ -372,6 +404,16 @@ fn map_tellerv2_events(blk: &eth::Block, events: &mut contract::Events) {
                         if let Some(event) =
                             abi::tellerv2_contract::events::SubmittedBid::match_and_decode(log)
                         {
+                            // the event does not carry the loan terms, so pull them from the submitBid call that emitted it.
+                            // a bid submitted through a contract we can't decode leaves the terms unset rather than zero
+                            let call_terms =
+                                find_submit_bid_call_terms(view.transaction, log.block_index);
+
+                            let (collateral, metadata_uri_text) = call_terms
+                                .as_ref()
+                                .map(|call_terms| (call_terms.collateral.clone(), call_terms.metadata_uri.clone()))
+                                .unwrap_or_default();
+
                             return Some(contract::Tellerv2SubmittedBid {
                                 evt_tx_hash: Hex(&view.transaction.hash).to_string(),
                                 evt_index: log.block_index,
@@ -381,6 +423,10 @@ fn map_tellerv2_events(blk: &eth::Block, events: &mut contract::Events) {
                                 borrower: event.borrower,
                                 metadata_uri: Vec::from(event.metadata_uri),
                                 receiver: event.receiver,
+                                apr: call_terms.as_ref().map(|call_terms| call_terms.apr),
+                                duration: call_terms.as_ref().map(|call_terms| call_terms.duration),
+                                collateral,
+                                metadata_uri_text,
                             });
                         }
 
@@ -411,6 +457,32 @@ fn map_tellerv2_events(blk: &eth::Block, events: &mut contract::Events) {
                 })
         })
         .collect());
+    events.tellerv2_upgradeds.append(
+        &mut blk
+            .receipts()
+            .flat_map(|view| {
+                view.receipt
+                    .logs
+                    .iter()
+                    .filter(|log| log.address == TELLERV2_TRACKED_CONTRACT)
+                    .filter_map(|log| {
+                        if let Some(event) =
+                            abi::tellerv2_contract::events::Upgraded::match_and_decode(log)
+                        {
+                            return Some(contract::Tellerv2Upgraded {
+                                evt_tx_hash: Hex(&view.transaction.hash).to_string(),
+                                evt_index: log.block_index,
+                                evt_block_time: Some(blk.timestamp().to_owned()),
+                                evt_block_number: blk.number,
+                                implementation: event.implementation,
+                            });
+                        }
+
+                        None
+                    })
+            })
+            .collect(),
+    );
     events.tellerv2_unpauseds.append(
         &mut blk
             .receipts()
@@ -439,20 +511,286 @@ fn map_tellerv2_events(blk: &eth::Block, events: &mut contract::Events) {
     );
 }
 
+struct SubmitBidCallTerms {
+    apr: u64,
+    duration: u64,
+    collateral: Vec<contract::Tellerv2BidCollateral>,
+    metadata_uri: String,
+}
+
 /*
-fn db_tellerv2_out(events: &contract::Events, tables: &mut DatabaseChangeTables) {
-    // Loop over all the abis events to create table changes
+  Finds the submitBid call (with or without collateral) that emitted the SubmittedBid log.
+  Matching on the log lets this work when the bid is submitted through a forwarder or the proxy.
+*/
+fn find_submit_bid_call_terms(
+    transaction: &eth::TransactionTrace,
+    log_block_index: u32,
+) -> Option<SubmitBidCallTerms> {
+    transaction
+        .calls
+        .iter()
+        .filter(|call| !call.state_reverted)
+        .filter(|call| {
+            call.logs
+                .iter()
+                .any(|call_log| call_log.block_index == log_block_index)
+        })
+        .find_map(|call| {
+            if abi::tellerv2_contract::functions::SubmitBid2::match_call(call) {
+                if let Ok(submit_bid) = abi::tellerv2_contract::functions::SubmitBid2::decode(call)
+                {
+                    return Some(SubmitBidCallTerms {
+                        apr: submit_bid.u_apr.to_u64(),
+                        duration: submit_bid.u_duration.to_u64(),
+                        collateral: submit_bid
+                            .u_collateral_info
+                            .iter()
+                            .map(|(collateral_type, amount, token_id, collateral_address)| {
+                                contract::Tellerv2BidCollateral {
+                                    collateral_type: collateral_type.to_u64() as u32,
+                                    collateral_address: collateral_address.clone(),
+                                    amount: amount.to_string(),
+                                    token_id: token_id.to_string(),
+                                }
+                            })
+                            .collect(),
+                        metadata_uri: submit_bid.u_metadata_uri,
+                    });
+                }
+            }
+
+            if abi::tellerv2_contract::functions::SubmitBid1::match_call(call) {
+                if let Ok(submit_bid) = abi::tellerv2_contract::functions::SubmitBid1::decode(call)
+                {
+                    return Some(SubmitBidCallTerms {
+                        apr: submit_bid.u_apr.to_u64(),
+                        duration: submit_bid.u_duration.to_u64(),
+                        collateral: Vec::new(),
+                        metadata_uri: submit_bid.u_metadata_uri,
+                    });
+                }
+            }
+
+            None
+        })
+}
+
+struct ForwarderStatusChange<'a> {
+    market_id: &'a String,
+
+    forwarder: &'a Vec<u8>,
+
+    sender: &'a Vec<u8>,
+
+    is_active: bool,
+
+    evt_block_time: &'a prost_types::Timestamp,
+
+    evt_block_number: u64,
+}
+
+/*
+  MarketForwarderApproved and MarketForwarderRenounced in log order, so the last one in the block decides is_active.
+*/
+fn forwarder_status_changes(events: &contract::Events) -> Vec<ForwarderStatusChange> {
+    let mut forwarder_status_changes: Vec<(u32, ForwarderStatusChange)> = Vec::new();
+
+    events.tellerv2_market_forwarder_approveds.iter().for_each(|evt| {
+        forwarder_status_changes.push((
+            evt.evt_index,
+            ForwarderStatusChange {
+                market_id: &evt.market_id,
+                forwarder: &evt.forwarder,
+                sender: &evt.sender,
+                is_active: true,
+                evt_block_time: evt.evt_block_time.as_ref().unwrap(),
+                evt_block_number: evt.evt_block_number,
+            },
+        ));
+    });
+    events.tellerv2_market_forwarder_renounceds.iter().for_each(|evt| {
+        forwarder_status_changes.push((
+            evt.evt_index,
+            ForwarderStatusChange {
+                market_id: &evt.market_id,
+                forwarder: &evt.forwarder,
+                sender: &evt.sender,
+                is_active: false,
+                evt_block_time: evt.evt_block_time.as_ref().unwrap(),
+                evt_block_number: evt.evt_block_number,
+            },
+        ));
+    });
+
+    forwarder_status_changes.sort_by_key(|(evt_index, _)| *evt_index);
+
+    forwarder_status_changes
+        .into_iter()
+        .map(|(_, forwarder_status_change)| forwarder_status_change)
+        .collect()
+}
+
+/*
+  Postgres side of graph_tellerv2_out.  Every row is an upsert so replays and bids first seen past the
+  initial block (accepted or repaid without a submitted row) land cleanly.
+*/
+fn db_tellerv2_out(
+    events: &contract::Events,
+
+    bid_loan_details_by_id: &BTreeMap<String, rpc::tellerv2::BidLoanDetailsData>,
+
+    token_address_delta_store: &Deltas<DeltaBigInt>,
+
+    token_prices: &StoreGetFloat64,
+
+    token_decimals: &StoreGetBigInt,
+
+    pause_window_store: &StoreGetString,
+
+    tables: &mut DatabaseChangeTables,
+) {
+    let teller_v2_address = Address::from_slice(&TELLERV2_TRACKED_CONTRACT);
+
+    let weth_address = H160::from_str("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2").unwrap();
+    let usdc_address = H160::from_str("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").unwrap();
+
+    events.tellerv2_submitted_bids.iter().for_each(|evt| {
+        let (metadata_uri_text, metadata_scheme) =
+            decode_metadata_uri(&evt.metadata_uri_text, &evt.metadata_uri);
+
+        let open_pause_window = open_pause_window_at(pause_window_store, evt.evt_index);
+
+        let submitted_bid_row = tables
+            .upsert_row("tellerv2_submitted_bid", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
+            .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
+            .set("evt_block_number", evt.evt_block_number)
+            .set("bid_id", BigDecimal::from_str(&evt.bid_id).unwrap())
+            .set("borrower", Hex(&evt.borrower).to_string())
+            .set("metadata_uri", Hex(&evt.metadata_uri).to_string())
+            .set("metadata_scheme", metadata_scheme.to_string())
+            .set("receiver", Hex(&evt.receiver).to_string())
+            .set("inside_pause_window", open_pause_window.is_some());
+
+        if let Some(metadata_uri_text) = metadata_uri_text {
+            submitted_bid_row.set("metadata_uri_text", metadata_uri_text);
+        }
+
+        if let Some((_, pause_window_id)) = open_pause_window {
+            submitted_bid_row.set("pause_window", pause_window_id);
+        }
+
+        let bid_id = BigInt::from_str(&evt.bid_id).unwrap();
+
+        let bid_row = tables
+            .upsert_row("tellerv2_bid", bid_id.to_string())
+            .set("status", "submitted".to_string())
+            .set("borrower", Hex(&evt.borrower).to_string())
+            .set("receiver", Hex(&evt.receiver).to_string())
+            .set("collateral_count", evt.collateral.len() as u64);
+
+        if let Some(apr) = evt.apr {
+            bid_row.set("apr_bps", apr);
+        }
+
+        if let Some(duration) = evt.duration {
+            bid_row.set("duration_seconds", duration);
+        }
+
+        evt.collateral.iter().enumerate().for_each(|(collateral_index, collateral)| {
+            tables
+                .upsert_row(
+                    "tellerv2_bid_collateral",
+                    [("bid_id", bid_id.to_string()),("collateral_index", collateral_index.to_string())],
+                )
+                .set("collateral_type", collateral.collateral_type as u64)
+                .set("collateral_address", Hex(&collateral.collateral_address).to_string())
+                .set("amount", BigDecimal::from_str(&collateral.amount).unwrap())
+                .set("token_id", BigDecimal::from_str(&collateral.token_id).unwrap());
+        });
+
+        // the expiration window is relative to the submission time; a zero window never expires
+        if let Some(bid_expiration_window) =
+            rpc::tellerv2::fetch_bid_expiration_time_from_rpc(&teller_v2_address, &bid_id)
+                .filter(|window| !window.is_zero())
+        {
+            let submitted_at = evt.evt_block_time.as_ref().unwrap();
+
+            tables.upsert_row("tellerv2_bid", bid_id.to_string()).set(
+                "expiration_time",
+                prost_types::Timestamp {
+                    seconds: submitted_at.seconds + bid_expiration_window.to_u64() as i64,
+                    nanos: 0,
+                },
+            );
+        }
+
+        if let Some(submitted_bid_data) = bid_loan_details_by_id.get(&evt.bid_id) {
+            let principal_amount_usdc = calculate_principal_amount_usdc(
+                submitted_bid_data.principal_amount.clone(),
+                submitted_bid_data.principal_token_address,
+                weth_address,
+                usdc_address,
+                token_prices,
+                token_decimals,
+            );
+
+            tables
+                .upsert_row("tellerv2_bid", bid_id.to_string())
+                .set(
+                    "principal_token_address",
+                    Hex(&submitted_bid_data.principal_token_address).to_string(),
+                )
+                .set("principal_amount", &submitted_bid_data.principal_amount)
+                .set("principal_amount_usdc", f64_to_bigdecimal(principal_amount_usdc));
+        }
+    });
     events.tellerv2_accepted_bids.iter().for_each(|evt| {
-        tables
-            .create_row("tellerv2_accepted_bid", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
+        let open_pause_window = open_pause_window_at(pause_window_store, evt.evt_index);
+
+        let accepted_bid_row = tables
+            .upsert_row("tellerv2_accepted_bid", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
             .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
             .set("evt_block_number", evt.evt_block_number)
             .set("bid_id", BigDecimal::from_str(&evt.bid_id).unwrap())
+            .set("lender", Hex(&evt.lender).to_string())
+            .set("inside_pause_window", open_pause_window.is_some());
+
+        if let Some((_, pause_window_id)) = open_pause_window {
+            accepted_bid_row.set("pause_window", pause_window_id);
+        }
+
+        let bid_id = BigInt::from_str(&evt.bid_id).unwrap();
+
+        let bid_row = tables
+            .upsert_row("tellerv2_bid", bid_id.to_string())
+            .set("status", "accepted".to_string())
+            .set("accepted_at", evt.evt_block_time.as_ref().unwrap())
             .set("lender", Hex(&evt.lender).to_string());
+
+        if let Some(submitted_bid_data) = bid_loan_details_by_id.get(&evt.bid_id) {
+            let principal_amount_usdc = calculate_principal_amount_usdc(
+                submitted_bid_data.principal_amount.clone(),
+                submitted_bid_data.principal_token_address,
+                weth_address,
+                usdc_address,
+                token_prices,
+                token_decimals,
+            );
+
+            bid_row
+                .set("borrower", Hex(&submitted_bid_data.borrower_address).to_string())
+                .set("lender", Hex(&submitted_bid_data.lender_address).to_string())
+                .set(
+                    "principal_token_address",
+                    Hex(&submitted_bid_data.principal_token_address).to_string(),
+                )
+                .set("principal_amount", &submitted_bid_data.principal_amount)
+                .set("principal_amount_usdc", f64_to_bigdecimal(principal_amount_usdc));
+        }
     });
     events.tellerv2_admin_changeds.iter().for_each(|evt| {
         tables
-            .create_row("tellerv2_admin_changed", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
+            .upsert_row("tellerv2_admin_changed", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
             .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
             .set("evt_block_number", evt.evt_block_number)
             .set("new_admin", Hex(&evt.new_admin).to_string())
@@ -460,14 +798,18 @@ fn db_tellerv2_out(events: &contract::Events, tables: &mut DatabaseChangeTables)
     });
     events.tellerv2_cancelled_bids.iter().for_each(|evt| {
         tables
-            .create_row("tellerv2_cancelled_bid", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
+            .upsert_row("tellerv2_cancelled_bid", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
             .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
             .set("evt_block_number", evt.evt_block_number)
             .set("bid_id", BigDecimal::from_str(&evt.bid_id).unwrap());
+
+        tables
+            .upsert_row("tellerv2_bid", evt.bid_id.clone())
+            .set("status", "cancelled".to_string());
     });
     events.tellerv2_fee_paids.iter().for_each(|evt| {
         tables
-            .create_row("tellerv2_fee_paid", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
+            .upsert_row("tellerv2_fee_paid", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
             .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
             .set("evt_block_number", evt.evt_block_number)
             .set("amount", BigDecimal::from_str(&evt.amount).unwrap())
@@ -476,36 +818,51 @@ fn db_tellerv2_out(events: &contract::Events, tables: &mut DatabaseChangeTables)
     });
     events.tellerv2_initializeds.iter().for_each(|evt| {
         tables
-            .create_row("tellerv2_initialized", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
+            .upsert_row("tellerv2_initialized", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
             .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
             .set("evt_block_number", evt.evt_block_number)
             .set("version", evt.version);
     });
     events.tellerv2_loan_liquidateds.iter().for_each(|evt| {
         tables
-            .create_row("tellerv2_loan_liquidated", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
+            .upsert_row("tellerv2_loan_liquidated", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
             .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
             .set("evt_block_number", evt.evt_block_number)
             .set("bid_id", BigDecimal::from_str(&evt.bid_id).unwrap())
             .set("liquidator", Hex(&evt.liquidator).to_string());
+
+        tables
+            .upsert_row("tellerv2_bid", evt.bid_id.clone())
+            .set("status", "liquidated".to_string());
     });
     events.tellerv2_loan_repaids.iter().for_each(|evt| {
         tables
-            .create_row("tellerv2_loan_repaid", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
+            .upsert_row("tellerv2_loan_repaid", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
             .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
             .set("evt_block_number", evt.evt_block_number)
             .set("bid_id", BigDecimal::from_str(&evt.bid_id).unwrap());
+
+        // liquidateLoanFull emits LoanRepaid before LoanLiquidated, the later status wins in the same block
+        if !events
+            .tellerv2_loan_liquidateds
+            .iter()
+            .any(|liquidated_evt| liquidated_evt.bid_id == evt.bid_id)
+        {
+            tables
+                .upsert_row("tellerv2_bid", evt.bid_id.clone())
+                .set("status", "repaid".to_string());
+        }
     });
     events.tellerv2_loan_repayments.iter().for_each(|evt| {
         tables
-            .create_row("tellerv2_loan_repayment", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
+            .upsert_row("tellerv2_loan_repayment", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
             .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
             .set("evt_block_number", evt.evt_block_number)
             .set("bid_id", BigDecimal::from_str(&evt.bid_id).unwrap());
     });
     events.tellerv2_market_forwarder_approveds.iter().for_each(|evt| {
         tables
-            .create_row("tellerv2_market_forwarder_approved", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
+            .upsert_row("tellerv2_market_forwarder_approved", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
             .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
             .set("evt_block_number", evt.evt_block_number)
             .set("forwarder", Hex(&evt.forwarder).to_string())
@@ -514,23 +871,31 @@ fn db_tellerv2_out(events: &contract::Events, tables: &mut DatabaseChangeTables)
     });
     events.tellerv2_market_forwarder_renounceds.iter().for_each(|evt| {
         tables
-            .create_row("tellerv2_market_forwarder_renounced", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
+            .upsert_row("tellerv2_market_forwarder_renounced", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
             .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
             .set("evt_block_number", evt.evt_block_number)
             .set("forwarder", Hex(&evt.forwarder).to_string())
             .set("market_id", BigDecimal::from_str(&evt.market_id).unwrap())
             .set("sender", Hex(&evt.sender).to_string());
     });
+
+    for forwarder_status_change in forwarder_status_changes(events) {
+        tables
+            .upsert_row("forwarder_approval", [("market_id", forwarder_status_change.market_id.clone()),("forwarder", Hex(forwarder_status_change.forwarder).to_string()),("sender", Hex(forwarder_status_change.sender).to_string())])
+            .set("is_active", forwarder_status_change.is_active)
+            .set("status_changed_at", forwarder_status_change.evt_block_time)
+            .set("status_changed_block_number", forwarder_status_change.evt_block_number);
+    }
     events.tellerv2_market_owner_cancelled_bids.iter().for_each(|evt| {
         tables
-            .create_row("tellerv2_market_owner_cancelled_bid", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
+            .upsert_row("tellerv2_market_owner_cancelled_bid", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
             .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
             .set("evt_block_number", evt.evt_block_number)
             .set("bid_id", BigDecimal::from_str(&evt.bid_id).unwrap());
     });
     events.tellerv2_ownership_transferreds.iter().for_each(|evt| {
         tables
-            .create_row("tellerv2_ownership_transferred", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
+            .upsert_row("tellerv2_ownership_transferred", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
             .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
             .set("evt_block_number", evt.evt_block_number)
             .set("new_owner", Hex(&evt.new_owner).to_string())
@@ -538,93 +903,378 @@ fn db_tellerv2_out(events: &contract::Events, tables: &mut DatabaseChangeTables)
     });
     events.tellerv2_pauseds.iter().for_each(|evt| {
         tables
-            .create_row("tellerv2_paused", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
+            .upsert_row("tellerv2_paused", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
             .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
             .set("evt_block_number", evt.evt_block_number)
             .set("account", Hex(&evt.account).to_string());
+
+        tables
+            .upsert_row("pause_window", format!("{}-{}", evt.evt_tx_hash, evt.evt_index))
+            .set("started_at", evt.evt_block_time.as_ref().unwrap())
+            .set("started_block_number", evt.evt_block_number)
+            .set("paused_by", Hex(&evt.account).to_string())
+            .set("is_open", true);
     });
     events.tellerv2_protocol_fee_sets.iter().for_each(|evt| {
         tables
-            .create_row("tellerv2_protocol_fee_set", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
+            .upsert_row("tellerv2_protocol_fee_set", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
             .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
             .set("evt_block_number", evt.evt_block_number)
             .set("new_fee", evt.new_fee)
             .set("old_fee", evt.old_fee);
     });
-    events.tellerv2_submitted_bids.iter().for_each(|evt| {
+    events.tellerv2_trusted_market_forwarder_sets.iter().for_each(|evt| {
         tables
-            .create_row("tellerv2_submitted_bid", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
+            .upsert_row("tellerv2_trusted_market_forwarder_set", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
             .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
             .set("evt_block_number", evt.evt_block_number)
-            .set("bid_id", BigDecimal::from_str(&evt.bid_id).unwrap())
-            .set("borrower", Hex(&evt.borrower).to_string())
-            .set("metadata_uri", Hex(&evt.metadata_uri).to_string())
-            .set("receiver", Hex(&evt.receiver).to_string());
+            .set("forwarder", Hex(&evt.forwarder).to_string())
+            .set("market_id", BigDecimal::from_str(&evt.market_id).unwrap())
+            .set("sender", Hex(&evt.sender).to_string());
+
+        tables
+            .upsert_row("market_forwarder", evt.market_id.clone())
+            .set("trusted_forwarder", Hex(&evt.forwarder).to_string())
+            .set("set_by", Hex(&evt.sender).to_string())
+            .set("updated_at", evt.evt_block_time.as_ref().unwrap())
+            .set("updated_block_number", evt.evt_block_number);
+    });
+    events.tellerv2_unpauseds.iter().for_each(|evt| {
+        tables
+            .upsert_row("tellerv2_unpaused", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
+            .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
+            .set("evt_block_number", evt.evt_block_number)
+            .set("account", Hex(&evt.account).to_string());
 
+        if let Some((started_at, pause_window_id)) =
+            open_pause_window_at(pause_window_store, evt.evt_index)
+        {
+            let ended_at = evt.evt_block_time.as_ref().unwrap();
 
+            tables
+                .upsert_row("pause_window", pause_window_id)
+                .set("ended_at", ended_at)
+                .set("ended_block_number", evt.evt_block_number)
+                .set("unpaused_by", Hex(&evt.account).to_string())
+                .set("duration_seconds", (ended_at.seconds - started_at) as u64)
+                .set("is_open", false);
+        }
+    });
+    events.tellerv2_upgradeds.iter().for_each(|evt| {
+        tables
+            .upsert_row("tellerv2_upgraded", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
+            .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
+            .set("evt_block_number", evt.evt_block_number)
+            .set("implementation", Hex(&evt.implementation).to_string());
+    });
 
-        let bid_id = BigInt::from_str(&evt.bid_id).unwrap();
-        let teller_v2_address = H160::from_slice( &TELLERV2_TRACKED_CONTRACT ) ;
+    for token_address_delta in token_address_delta_store.iter() {
+        let ord = 0; // FOR NOW
 
-        let submitted_bid_data = rpc::fetch_loan_summary_from_rpc(
+        let token_address = &token_address_delta.key;
 
-            &teller_v2_address,
-            &bid_id
+        let WETH_ADDRESS = "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2";
 
-        );
+        if let Some(token_price) = token_prices.get_at(ord, token_address) {
+            tables
+                .upsert_row("token_price", token_address.clone())
+                .set("reference_token_address", WETH_ADDRESS)
+                .set("price_ratio", token_price.to_string());
+        }
+    }
+}
 
+/*
+  ClickHouse side of db_tellerv2_out.  The ClickHouse sink only inserts, so nothing here is updated in place:
 
-        if let Some(submitted_bid_data) = submitted_bid_data {
+  - event tables are append only
+  - tellerv2_bid gets one full snapshot per bid per block, ReplacingMergeTree keeps the newest
+  - token_price keeps the whole price history, one row per token per block
+*/
+fn db_clickhouse_tellerv2_out(
+    clock: &Clock,
 
-            let bid_id = submitted_bid_data.bid_id;
+    events: &contract::Events,
 
-            tables
-            .create_row("tellerv2_bid",  bid_id.to_string() )
-            .set("bid_id",  &bid_id )
-            .set("borrower", Hex(&submitted_bid_data.borrower_address).to_string())
-            .set("lender", Hex(&submitted_bid_data.lender_address).to_string())
-            .set("receiver", Hex(&evt.receiver).to_string())
-            .set("principal_token_address", Hex(&submitted_bid_data.principal_token_address).to_string())
-            .set("principal_amount",  &submitted_bid_data.principal_amount)
+    bid_loan_details_by_id: &BTreeMap<String, rpc::tellerv2::BidLoanDetailsData>,
 
-            ;
+    token_address_delta_store: &Deltas<DeltaBigInt>,
 
+    token_prices: &StoreGetFloat64,
 
+    token_decimals: &StoreGetBigInt,
 
-        }
+    pause_window_store: &StoreGetString,
 
+    tables: &mut DatabaseChangeTables,
+) {
+    let block_time = clock.timestamp.as_ref().unwrap().clone();
 
+    let weth_address = H160::from_str("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2").unwrap();
+    let usdc_address = H160::from_str("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").unwrap();
 
+    let mut bid_ids_touched: BTreeSet<String> = BTreeSet::new();
 
+    events.tellerv2_accepted_bids.iter().for_each(|evt| {
+        let open_pause_window = open_pause_window_at(pause_window_store, evt.evt_index);
 
+        let accepted_bid_row = tables
+            .create_row("tellerv2_accepted_bid", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
+            .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
+            .set("evt_block_number", evt.evt_block_number)
+            .set("bid_id", &evt.bid_id)
+            .set("lender", Hex(&evt.lender).to_string())
+            .set("inside_pause_window", open_pause_window.is_some());
 
+        if let Some((_, pause_window_id)) = open_pause_window {
+            accepted_bid_row.set("pause_window", pause_window_id);
+        }
 
+        bid_ids_touched.insert(evt.bid_id.clone());
     });
-    events.tellerv2_trusted_market_forwarder_sets.iter().for_each(|evt| {
+    events.tellerv2_admin_changeds.iter().for_each(|evt| {
         tables
-            .create_row("tellerv2_trusted_market_forwarder_set", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
+            .create_row("tellerv2_admin_changed", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
             .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
             .set("evt_block_number", evt.evt_block_number)
-            .set("forwarder", Hex(&evt.forwarder).to_string())
-            .set("market_id", BigDecimal::from_str(&evt.market_id).unwrap())
-            .set("sender", Hex(&evt.sender).to_string());
+            .set("new_admin", Hex(&evt.new_admin).to_string())
+            .set("previous_admin", Hex(&evt.previous_admin).to_string());
     });
-    events.tellerv2_unpauseds.iter().for_each(|evt| {
+    events.tellerv2_cancelled_bids.iter().for_each(|evt| {
         tables
-            .create_row("tellerv2_unpaused", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
+            .create_row("tellerv2_cancelled_bid", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
             .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
             .set("evt_block_number", evt.evt_block_number)
-            .set("account", Hex(&evt.account).to_string());
+            .set("bid_id", &evt.bid_id);
+
+        bid_ids_touched.insert(evt.bid_id.clone());
     });
-    events.tellerv2_upgradeds.iter().for_each(|evt| {
+    events.tellerv2_fee_paids.iter().for_each(|evt| {
         tables
-            .create_row("tellerv2_upgraded", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
+            .create_row("tellerv2_fee_paid", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
             .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
             .set("evt_block_number", evt.evt_block_number)
-            .set("implementation", Hex(&evt.implementation).to_string());
+            .set("amount", &evt.amount)
+            .set("bid_id", &evt.bid_id)
+            .set("fee_type", &evt.fee_type);
+    });
+    events.tellerv2_initializeds.iter().for_each(|evt| {
+        tables
+            .create_row("tellerv2_initialized", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
+            .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
+            .set("evt_block_number", evt.evt_block_number)
+            .set("version", evt.version);
+    });
+    events.tellerv2_loan_liquidateds.iter().for_each(|evt| {
+        tables
+            .create_row("tellerv2_loan_liquidated", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
+            .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
+            .set("evt_block_number", evt.evt_block_number)
+            .set("bid_id", &evt.bid_id)
+            .set("liquidator", Hex(&evt.liquidator).to_string());
+
+        bid_ids_touched.insert(evt.bid_id.clone());
+    });
+    events.tellerv2_loan_repaids.iter().for_each(|evt| {
+        tables
+            .create_row("tellerv2_loan_repaid", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
+            .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
+            .set("evt_block_number", evt.evt_block_number)
+            .set("bid_id", &evt.bid_id);
+
+        bid_ids_touched.insert(evt.bid_id.clone());
+    });
+    events.tellerv2_loan_repayments.iter().for_each(|evt| {
+        tables
+            .create_row("tellerv2_loan_repayment", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
+            .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
+            .set("evt_block_number", evt.evt_block_number)
+            .set("bid_id", &evt.bid_id);
+
+        bid_ids_touched.insert(evt.bid_id.clone());
+    });
+    events.tellerv2_market_forwarder_approveds.iter().for_each(|evt| {
+        tables
+            .create_row("tellerv2_market_forwarder_approved", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
+            .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
+            .set("evt_block_number", evt.evt_block_number)
+            .set("forwarder", Hex(&evt.forwarder).to_string())
+            .set("market_id", &evt.market_id)
+            .set("sender", Hex(&evt.sender).to_string());
+    });
+    events.tellerv2_market_forwarder_renounceds.iter().for_each(|evt| {
+        tables
+            .create_row("tellerv2_market_forwarder_renounced", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
+            .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
+            .set("evt_block_number", evt.evt_block_number)
+            .set("forwarder", Hex(&evt.forwarder).to_string())
+            .set("market_id", &evt.market_id)
+            .set("sender", Hex(&evt.sender).to_string());
+    });
+    events.tellerv2_market_owner_cancelled_bids.iter().for_each(|evt| {
+        tables
+            .create_row("tellerv2_market_owner_cancelled_bid", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
+            .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
+            .set("evt_block_number", evt.evt_block_number)
+            .set("bid_id", &evt.bid_id);
+
+        bid_ids_touched.insert(evt.bid_id.clone());
+    });
+    events.tellerv2_ownership_transferreds.iter().for_each(|evt| {
+        tables
+            .create_row("tellerv2_ownership_transferred", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
+            .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
+            .set("evt_block_number", evt.evt_block_number)
+            .set("new_owner", Hex(&evt.new_owner).to_string())
+            .set("previous_owner", Hex(&evt.previous_owner).to_string());
+    });
+    events.tellerv2_pauseds.iter().for_each(|evt| {
+        tables
+            .create_row("tellerv2_paused", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
+            .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
+            .set("evt_block_number", evt.evt_block_number)
+            .set("account", Hex(&evt.account).to_string());
+    });
+    events.tellerv2_protocol_fee_sets.iter().for_each(|evt| {
+        tables
+            .create_row("tellerv2_protocol_fee_set", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
+            .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
+            .set("evt_block_number", evt.evt_block_number)
+            .set("new_fee", evt.new_fee)
+            .set("old_fee", evt.old_fee);
+    });
+    events.tellerv2_submitted_bids.iter().for_each(|evt| {
+        let (metadata_uri_text, metadata_scheme) =
+            decode_metadata_uri(&evt.metadata_uri_text, &evt.metadata_uri);
+
+        let open_pause_window = open_pause_window_at(pause_window_store, evt.evt_index);
+
+        let submitted_bid_row = tables
+            .create_row("tellerv2_submitted_bid", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
+            .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
+            .set("evt_block_number", evt.evt_block_number)
+            .set("bid_id", &evt.bid_id)
+            .set("borrower", Hex(&evt.borrower).to_string())
+            .set("metadata_uri", Hex(&evt.metadata_uri).to_string())
+            .set("metadata_scheme", metadata_scheme.to_string())
+            .set("receiver", Hex(&evt.receiver).to_string())
+            .set("collateral_count", evt.collateral.len() as u64)
+            .set("inside_pause_window", open_pause_window.is_some());
+
+        if let Some(apr) = evt.apr {
+            submitted_bid_row.set("apr_bps", apr);
+        }
+
+        if let Some(duration) = evt.duration {
+            submitted_bid_row.set("duration_seconds", duration);
+        }
+
+        if let Some(metadata_uri_text) = metadata_uri_text {
+            submitted_bid_row.set("metadata_uri_text", metadata_uri_text);
+        }
+
+        if let Some((_, pause_window_id)) = open_pause_window {
+            submitted_bid_row.set("pause_window", pause_window_id);
+        }
+
+        // collateral declared at submission, it can't change afterwards so one insert per bid is enough
+        evt.collateral.iter().enumerate().for_each(|(collateral_index, collateral)| {
+            tables
+                .create_row(
+                    "tellerv2_bid_collateral",
+                    [("bid_id", evt.bid_id.clone()),("collateral_index", collateral_index.to_string())],
+                )
+                .set("collateral_type", collateral.collateral_type as u64)
+                .set("collateral_address", Hex(&collateral.collateral_address).to_string())
+                .set("amount", &collateral.amount)
+                .set("token_id", &collateral.token_id);
+        });
+
+        bid_ids_touched.insert(evt.bid_id.clone());
+    });
+    events.tellerv2_trusted_market_forwarder_sets.iter().for_each(|evt| {
+        tables
+            .create_row("tellerv2_trusted_market_forwarder_set", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
+            .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
+            .set("evt_block_number", evt.evt_block_number)
+            .set("forwarder", Hex(&evt.forwarder).to_string())
+            .set("market_id", &evt.market_id)
+            .set("sender", Hex(&evt.sender).to_string());
+    });
+    events.tellerv2_unpauseds.iter().for_each(|evt| {
+        tables
+            .create_row("tellerv2_unpaused", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
+            .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
+            .set("evt_block_number", evt.evt_block_number)
+            .set("account", Hex(&evt.account).to_string());
+    });
+    events.tellerv2_upgradeds.iter().for_each(|evt| {
+        tables
+            .create_row("tellerv2_upgraded", [("evt_tx_hash", evt.evt_tx_hash.to_string()),("evt_index", evt.evt_index.to_string())])
+            .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
+            .set("evt_block_number", evt.evt_block_number)
+            .set("implementation", Hex(&evt.implementation).to_string());
     });
+
+    // a partial row would replace the full one, so bids without bids() data are left to the event tables
+    for bid_id in bid_ids_touched {
+        if let Some(bid_loan_details) = bid_loan_details_by_id.get(&bid_id) {
+            let principal_amount_usdc = calculate_principal_amount_usdc(
+                bid_loan_details.principal_amount.clone(),
+                bid_loan_details.principal_token_address,
+                weth_address,
+                usdc_address,
+                token_prices,
+                token_decimals,
+            );
+
+            tables
+                .create_row("tellerv2_bid", [("bid_id", bid_id.clone()),("updated_block_number", clock.number.to_string())])
+                .set("updated_at", &block_time)
+                .set("status", bid_state_name(bid_loan_details.bid_state.to_u64()))
+                .set("borrower", Hex(&bid_loan_details.borrower_address).to_string())
+                .set("lender", Hex(&bid_loan_details.lender_address).to_string())
+                .set("market_id", &bid_loan_details.market_id)
+                .set("principal_token_address", Hex(&bid_loan_details.principal_token_address).to_string())
+                .set("principal_amount", &bid_loan_details.principal_amount)
+                .set("principal_amount_usdc", principal_amount_usdc.to_string())
+                .set("total_repaid_principal", &bid_loan_details.total_repaid_principal)
+                .set("total_repaid_interest", &bid_loan_details.total_repaid_interest)
+                .set("apr_bps", &bid_loan_details.apr)
+                .set("accepted_timestamp", bid_loan_details.accepted_timestamp.to_u64())
+                .set("last_repaid_timestamp", bid_loan_details.last_repaid_timestamp.to_u64());
+        }
+    }
+
+    for token_address_delta in token_address_delta_store.iter() {
+        let ord = 0; // FOR NOW
+
+        let token_address = &token_address_delta.key;
+
+        let WETH_ADDRESS = "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2";
+
+        if let Some(token_price) = token_prices.get_at(ord, token_address) {
+            tables
+                .create_row("token_price", [("base_token_address", token_address.clone()),("block_number", clock.number.to_string())])
+                .set("block_time", &block_time)
+                .set("reference_token_address", WETH_ADDRESS)
+                .set("price_ratio", token_price.to_string());
+        }
+    }
+}
+
+// BidState enum in TellerV2, named the same way as tellerv2_bid.status in graph_out
+fn bid_state_name(bid_state: u64) -> &'static str {
+    match bid_state {
+        1 => "submitted",
+        2 => "cancelled",
+        rpc::tellerv2::BID_STATE_ACCEPTED => "accepted",
+        rpc::tellerv2::BID_STATE_PAID => "repaid",
+        rpc::tellerv2::BID_STATE_LIQUIDATED => "liquidated",
+        6 => "closed",
+        _ => "nonexistent",
+    }
 }
-*/
 
 /*
 
@@ -635,22 +1285,19 @@ any block wiht any activity -- get price of ETH / USDC ? as multiplier..
 fn store_token_interaction_deltas(
     events: contract::Events,
 
+    bid_loan_details_list: teller::BidLoanDetailsList,
+
     token_address_delta_store: StoreAddBigInt, //just use a flag..   key is address as string
 ) {
     //FOR NOW .. CAN CAUSE ISSUES
     let ord = 0;
 
+    let bid_loan_details_by_id = bid_loan_details_by_id(&bid_loan_details_list);
+
     let mut activity_occured = false;
 
     events.tellerv2_submitted_bids.iter().for_each(|evt| {
-        let bid_id = BigInt::from_str(&evt.bid_id).unwrap();
-        let teller_v2_address = Address::from_slice(&TELLERV2_TRACKED_CONTRACT);
-
-        // this fails for very old bids as this fn wasnt added until later
-        let submitted_bid_data_option =
-            rpc::tellerv2::fetch_loan_summary_from_rpc(&teller_v2_address, &bid_id);
-
-        if let Some(submitted_bid_data) = submitted_bid_data_option {
+        if let Some(submitted_bid_data) = bid_loan_details_by_id.get(&evt.bid_id) {
             let store_key: String = address_to_string(&submitted_bid_data.principal_token_address);
             token_address_delta_store.add(ord, &store_key, BigInt::one());
 
@@ -660,14 +1307,7 @@ fn store_token_interaction_deltas(
 
     // Loop over all the abis events to create table changes
     events.tellerv2_accepted_bids.iter().for_each(|evt| {
-        let bid_id = BigInt::from_str(&evt.bid_id).unwrap();
-        let teller_v2_address = Address::from_slice(&TELLERV2_TRACKED_CONTRACT);
-
-        // this fails for very old bids as this fn wasnt added until later
-        let submitted_bid_data_option =
-            rpc::tellerv2::fetch_loan_summary_from_rpc(&teller_v2_address, &bid_id);
-
-        if let Some(submitted_bid_data) = submitted_bid_data_option {
+        if let Some(submitted_bid_data) = bid_loan_details_by_id.get(&evt.bid_id) {
             let store_key: String = address_to_string(&submitted_bid_data.principal_token_address);
             token_address_delta_store.add(ord, &store_key, BigInt::one());
 
@@ -676,14 +1316,7 @@ fn store_token_interaction_deltas(
     });
 
     events.tellerv2_loan_liquidateds.iter().for_each(|evt| {
-        let bid_id = BigInt::from_str(&evt.bid_id).unwrap();
-        let teller_v2_address = Address::from_slice(&TELLERV2_TRACKED_CONTRACT);
-
-        // this fails for very old bids as this fn wasnt added until later
-        let submitted_bid_data_option =
-            rpc::tellerv2::fetch_loan_summary_from_rpc(&teller_v2_address, &bid_id);
-
-        if let Some(submitted_bid_data) = submitted_bid_data_option {
+        if let Some(submitted_bid_data) = bid_loan_details_by_id.get(&evt.bid_id) {
             let store_key: String = address_to_string(&submitted_bid_data.principal_token_address);
             token_address_delta_store.add(ord, &store_key, BigInt::one());
 
@@ -691,14 +1324,16 @@ fn store_token_interaction_deltas(
         }
     });
     events.tellerv2_loan_repaids.iter().for_each(|evt| {
-        let bid_id = BigInt::from_str(&evt.bid_id).unwrap();
-        let teller_v2_address = Address::from_slice(&TELLERV2_TRACKED_CONTRACT);
+        if let Some(submitted_bid_data) = bid_loan_details_by_id.get(&evt.bid_id) {
+            let store_key: String = address_to_string(&submitted_bid_data.principal_token_address);
+            token_address_delta_store.add(ord, &store_key, BigInt::one());
 
-        // this fails for very old bids as this fn wasnt added until later
-        let submitted_bid_data_option =
-            rpc::tellerv2::fetch_loan_summary_from_rpc(&teller_v2_address, &bid_id);
+            activity_occured = true;
+        }
+    });
 
-        if let Some(submitted_bid_data) = submitted_bid_data_option {
+    events.tellerv2_loan_repayments.iter().for_each(|evt| {
+        if let Some(submitted_bid_data) = bid_loan_details_by_id.get(&evt.bid_id) {
             let store_key: String = address_to_string(&submitted_bid_data.principal_token_address);
             token_address_delta_store.add(ord, &store_key, BigInt::one());
 
@@ -798,427 +1433,3282 @@ fn store_uniswap_prices_for_tokens(
     }
 }
 
-fn graph_tellerv2_out(
-    events: &contract::Events,
+/*
+  bids(uint256) for every bid touched by a TellerV2 event in the block, so each bid is read from the contract once
+  and every store downstream sees the same end of block state.
+*/
+#[substreams::handlers::map]
+fn map_bid_loan_details(
+    events: contract::Events,
+) -> Result<teller::BidLoanDetailsList, substreams::errors::Error> {
+    let teller_v2_address = Address::from_slice(&TELLERV2_TRACKED_CONTRACT);
 
-    token_address_delta_store: &Deltas<DeltaBigInt>,
+    let mut bid_ids_touched: BTreeSet<String> = BTreeSet::new();
 
-    token_prices: &StoreGetFloat64,
+    events.tellerv2_submitted_bids.iter().for_each(|evt| {
+        bid_ids_touched.insert(evt.bid_id.clone());
+    });
+    events.tellerv2_accepted_bids.iter().for_each(|evt| {
+        bid_ids_touched.insert(evt.bid_id.clone());
+    });
+    events.tellerv2_cancelled_bids.iter().for_each(|evt| {
+        bid_ids_touched.insert(evt.bid_id.clone());
+    });
+    events.tellerv2_market_owner_cancelled_bids.iter().for_each(|evt| {
+        bid_ids_touched.insert(evt.bid_id.clone());
+    });
+    events.tellerv2_loan_repayments.iter().for_each(|evt| {
+        bid_ids_touched.insert(evt.bid_id.clone());
+    });
+    events.tellerv2_loan_repaids.iter().for_each(|evt| {
+        bid_ids_touched.insert(evt.bid_id.clone());
+    });
+    events.tellerv2_loan_liquidateds.iter().for_each(|evt| {
+        bid_ids_touched.insert(evt.bid_id.clone());
+    });
+    events.tellerv2_fee_paids.iter().for_each(|evt| {
+        bid_ids_touched.insert(evt.bid_id.clone());
+    });
 
-    token_decimals: &StoreGetBigInt,
+    let mut bid_loan_details_list = teller::BidLoanDetailsList::default();
 
-    tables: &mut EntityChangesTables,
-) {
-    events.tellerv2_submitted_bids.iter().for_each(|evt| {
-        tables
-            .create_row(
-                "tellerv2_submitted_bid",
-                format!("{}-{}", evt.evt_tx_hash, evt.evt_index),
-            )
-            .set("evt_tx_hash", &evt.evt_tx_hash)
-            .set("evt_index", evt.evt_index)
-            .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
-            .set("evt_block_number", evt.evt_block_number)
-            .set("bid_id", BigDecimal::from_str(&evt.bid_id).unwrap())
-            .set("borrower", Hex(&evt.borrower).to_string())
-            .set("metadata_uri", Hex(&evt.metadata_uri).to_string())
-            .set("receiver", Hex(&evt.receiver).to_string());
+    for bid_id in bid_ids_touched {
+        let bid_loan_details = match rpc::tellerv2::fetch_bid_loan_details_from_rpc(
+            &teller_v2_address,
+            &BigInt::from_str(&bid_id).unwrap(),
+        ) {
+            Some(bid_loan_details) => bid_loan_details,
+            None => continue,
+        };
+
+        bid_loan_details_list.bids.push(teller::BidLoanDetails {
+            bid_id,
+            borrower: bid_loan_details.borrower_address.as_bytes().to_vec(),
+            lender: bid_loan_details.lender_address.as_bytes().to_vec(),
+            market_id: bid_loan_details.market_id.to_string(),
+            principal_token_address: bid_loan_details.principal_token_address.as_bytes().to_vec(),
+            principal_amount: bid_loan_details.principal_amount.to_string(),
+            total_repaid_principal: bid_loan_details.total_repaid_principal.to_string(),
+            total_repaid_interest: bid_loan_details.total_repaid_interest.to_string(),
+            accepted_timestamp: bid_loan_details.accepted_timestamp.to_string(),
+            last_repaid_timestamp: bid_loan_details.last_repaid_timestamp.to_string(),
+            loan_duration: bid_loan_details.loan_duration.to_string(),
+            payment_cycle_amount: bid_loan_details.payment_cycle_amount.to_string(),
+            payment_cycle: bid_loan_details.payment_cycle.to_string(),
+            apr: bid_loan_details.apr.to_string(),
+            bid_state: bid_loan_details.bid_state.to_string(),
+            payment_type: bid_loan_details.payment_type.to_string(),
+        });
+    }
 
-        
-             
-            
-            
-        let bid_id = BigInt::from_str(&evt.bid_id).unwrap();
-        let teller_v2_address = Address::from_slice(&TELLERV2_TRACKED_CONTRACT);
-    
-        
-        tables
-                .create_row("tellerv2_bid", bid_id.to_string())
-                .set("bid_id", &bid_id)
-                .set("status", "submitted".to_string())
-                
-                .set(
-                    "borrower",
-                    Hex(  &evt.borrower ) .to_string(),
-                )
-                
-                ;
-        // this fails for very old bids as this fn wasnt added until later
-        let submitted_bid_data_option =
-            rpc::tellerv2::fetch_loan_summary_from_rpc(&teller_v2_address, &bid_id);
-            
-      if let Some(submitted_bid_data) = submitted_bid_data_option {
-          
-          let bid_id = bid_id.clone();
+    Ok(bid_loan_details_list)
+}
 
-            let weth_address =
-                H160::from_str("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2").unwrap();
-            let usdc_address =
-                H160::from_str("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").unwrap();
+/*
+  map_bid_loan_details output keyed by bid id, back in the shape fetch_bid_loan_details_from_rpc returns.
+*/
+fn bid_loan_details_by_id(
+    bid_loan_details_list: &teller::BidLoanDetailsList,
+) -> BTreeMap<String, rpc::tellerv2::BidLoanDetailsData> {
+    bid_loan_details_list
+        .bids
+        .iter()
+        .map(|bid_loan_details| {
+            (
+                bid_loan_details.bid_id.clone(),
+                rpc::tellerv2::BidLoanDetailsData {
+                    bid_id: BigInt::from_str(&bid_loan_details.bid_id).unwrap(),
+                    borrower_address: Address::from_slice(&bid_loan_details.borrower),
+                    lender_address: Address::from_slice(&bid_loan_details.lender),
+                    market_id: BigInt::from_str(&bid_loan_details.market_id).unwrap(),
+                    principal_token_address: Address::from_slice(
+                        &bid_loan_details.principal_token_address,
+                    ),
+                    principal_amount: BigInt::from_str(&bid_loan_details.principal_amount).unwrap(),
+                    total_repaid_principal: BigInt::from_str(&bid_loan_details.total_repaid_principal)
+                        .unwrap(),
+                    total_repaid_interest: BigInt::from_str(&bid_loan_details.total_repaid_interest)
+                        .unwrap(),
+                    accepted_timestamp: BigInt::from_str(&bid_loan_details.accepted_timestamp).unwrap(),
+                    last_repaid_timestamp: BigInt::from_str(&bid_loan_details.last_repaid_timestamp)
+                        .unwrap(),
+                    loan_duration: BigInt::from_str(&bid_loan_details.loan_duration).unwrap(),
+                    payment_cycle_amount: BigInt::from_str(&bid_loan_details.payment_cycle_amount)
+                        .unwrap(),
+                    payment_cycle: BigInt::from_str(&bid_loan_details.payment_cycle).unwrap(),
+                    apr: BigInt::from_str(&bid_loan_details.apr).unwrap(),
+                    bid_state: BigInt::from_str(&bid_loan_details.bid_state).unwrap(),
+                    payment_type: BigInt::from_str(&bid_loan_details.payment_type).unwrap(),
+                },
+            )
+        })
+        .collect()
+}
 
-            let principal_token_address = submitted_bid_data.principal_token_address.clone();
-            let principal_amount = submitted_bid_data.principal_amount.clone();
+/*
+  Cumulative totalRepaid per bid, read back from the contract whenever a bid is repaid or liquidated.
+  The repayment events do not carry amounts so the deltas of this store are what tell us how much came back.
 
-            let principal_amount_usdc = calculate_principal_amount_usdc(
-                principal_amount,
-                principal_token_address,
-                weth_address,
-                usdc_address,
-                &token_prices,
-                &token_decimals,
-            );
-            
-            let principal_amount_usdc_big_decimal = f64_to_bigdecimal(principal_amount_usdc);
-            
-            
-            
-                 tables
-                .update_row("tellerv2_bid", bid_id.to_string())
-               
-                .set("status", "submitted".to_string())
-                 
-                // .set("receiver", Hex(&evt.receiver).to_string())
-                .set(
-                    "principal_token_address",
-                    Hex(&submitted_bid_data.principal_token_address).to_string(),
-                )
-                .set("principal_amount", &submitted_bid_data.principal_amount)
-                .set("principal_amount_usdc", &principal_amount_usdc_big_decimal);
-            
-         }
-            
-        // }.unwrap()
+  bid_repaid_principal:{principal_token}:{bid_id}
+  bid_repaid_interest:{principal_token}:{bid_id}
+*/
+#[substreams::handlers::store]
+fn store_bid_repaid_totals(
+    events: contract::Events,
+    bid_loan_details_list: teller::BidLoanDetailsList,
+    bigint_set_store: StoreSetBigInt,
+) {
+    let ord = 0; // FOR NOW
+
+    let bid_loan_details_by_id = bid_loan_details_by_id(&bid_loan_details_list);
+
+    let mut bid_ids_touched: BTreeSet<String> = BTreeSet::new();
+
+    events.tellerv2_loan_repayments.iter().for_each(|evt| {
+        bid_ids_touched.insert(evt.bid_id.clone());
+    });
+    events.tellerv2_loan_repaids.iter().for_each(|evt| {
+        bid_ids_touched.insert(evt.bid_id.clone());
+    });
+    events.tellerv2_loan_liquidateds.iter().for_each(|evt| {
+        bid_ids_touched.insert(evt.bid_id.clone());
     });
 
-    // Loop over all the abis events to create table changes
+    for bid_id in bid_ids_touched {
+        if let Some(bid_loan_details) = bid_loan_details_by_id.get(&bid_id) {
+            bigint_set_store.set(
+                ord,
+                format!(
+                    "bid_repaid_principal:{}:{}",
+                    address_to_string(&bid_loan_details.principal_token_address),
+                    bid_id
+                ),
+                &bid_loan_details.total_repaid_principal,
+            );
+            bigint_set_store.set(
+                ord,
+                format!(
+                    "bid_repaid_interest:{}:{}",
+                    address_to_string(&bid_loan_details.principal_token_address),
+                    bid_id
+                ),
+                &bid_loan_details.total_repaid_interest,
+            );
+        }
+    }
+}
+
+/*
+  Running principal balance sheet per principal token, in token units.
+
+  token_principal:{token}:{outstanding|originated|repaid|liquidated}  -- all time
+  token_principal_daily:{token}:{day_index}:{originated|repaid|liquidated}  -- flows for that day
+*/
+#[substreams::handlers::store]
+fn store_token_principal(
+    clock: Clock,
+    events: contract::Events,
+    bid_loan_details_list: teller::BidLoanDetailsList,
+    bid_repaid_totals_deltas: Deltas<DeltaBigInt>,
+    bigint_add_store: StoreAddBigInt,
+) {
+    let ord = 0; // FOR NOW
+
+    let day_index = clock.timestamp.as_ref().unwrap().seconds / 86400;
+
+    let bid_loan_details_by_id = bid_loan_details_by_id(&bid_loan_details_list);
+
     events.tellerv2_accepted_bids.iter().for_each(|evt| {
-        
-        let event_block_time =  evt.evt_block_time.as_ref().unwrap().clone();
-        
-        tables
-            .create_row(
-                "tellerv2_accepted_bid",
-                format!("{}-{}", evt.evt_tx_hash, evt.evt_index),
-            )
-            .set("evt_tx_hash", &evt.evt_tx_hash)
-            .set("evt_index", evt.evt_index)
-            .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
-            .set("evt_block_number", evt.evt_block_number)
-            .set("bid_id", BigDecimal::from_str(&evt.bid_id).unwrap())
-            .set("lender", Hex(&evt.lender).to_string());
+        if let Some(bid_loan_details) = bid_loan_details_by_id.get(&evt.bid_id) {
+            let principal_token = address_to_string(&bid_loan_details.principal_token_address);
+            let principal_amount = &bid_loan_details.principal_amount;
 
-        let bid_id = BigInt::from_str(&evt.bid_id).unwrap();
-        let teller_v2_address = Address::from_slice(&TELLERV2_TRACKED_CONTRACT);
+            bigint_add_store.add(
+                ord,
+                format!("token_principal:{}:outstanding", principal_token),
+                principal_amount,
+            );
+            bigint_add_store.add(
+                ord,
+                format!("token_principal:{}:originated", principal_token),
+                principal_amount,
+            );
+            bigint_add_store.add(
+                ord,
+                format!(
+                    "token_principal_daily:{}:{}:originated",
+                    principal_token, day_index
+                ),
+                principal_amount,
+            );
+        }
+    });
 
-        // this fails for very old bids as this fn wasnt added until later
-        let submitted_bid_data_option =
-            rpc::tellerv2::fetch_loan_summary_from_rpc(&teller_v2_address, &bid_id);
+    for delta in bid_repaid_totals_deltas.iter() {
+        if substreams::key::segment_at(&delta.key, 0) != "bid_repaid_principal" {
+            continue;
+        }
 
-        /*
+        let principal_token = substreams::key::segment_at(&delta.key, 1);
+        let bid_id = substreams::key::segment_at(&delta.key, 2);
 
+        let principal_returned = delta.new_value.clone() - delta.old_value.clone();
 
-            The block stream encountered a substreams fatal error and will not retry: rpc error: code = InvalidArgument desc = step new irr: handler step new: execute modules: applying executor results "graph_out" on block 15096143: execute: maps wasm call: block 15096143: module "graph_out": general wasm execution panicked: wasm execution failed deterministically: panic in the wasm: "called `Option::unwrap()` on a `None` value" at src/lib.rs:750:15
+        if principal_returned <= BigInt::zero() {
+            continue;
+        }
 
-        */
+        // liquidateLoanFull pays the remaining principal through _repayLoan, so that part is counted as liquidated principal rather than repaid
+        let was_liquidated = events
+            .tellerv2_loan_liquidateds
+            .iter()
+            .any(|evt| evt.bid_id == bid_id);
+
+        let flow_type = match was_liquidated {
+            true => "liquidated",
+            false => "repaid",
+        };
+
+        bigint_add_store.add(
+            ord,
+            format!("token_principal:{}:outstanding", principal_token),
+            BigInt::zero() - principal_returned.clone(),
+        );
+        bigint_add_store.add(
+            ord,
+            format!("token_principal:{}:{}", principal_token, flow_type),
+            &principal_returned,
+        );
+        bigint_add_store.add(
+            ord,
+            format!(
+                "token_principal_daily:{}:{}:{}",
+                principal_token, day_index, flow_type
+            ),
+            &principal_returned,
+        );
+    }
+}
 
-        //  if let Some(submitted_bid_data) = submitted_bid_data {
-        if let Some(submitted_bid_data) = submitted_bid_data_option {
-            let bid_id = bid_id.clone();
+/*
+  Protocol wide principal in USDC, summed over every principal token.
 
-            let weth_address =
-                H160::from_str("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2").unwrap();
-            let usdc_address =
-                H160::from_str("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").unwrap();
+  Each movement is converted at the price of the block it happened in, so outstanding here is a book value.
+  token_day_data carries the mark to market value per token.
+*/
+#[substreams::handlers::store]
+fn store_protocol_principal_usdc(
+    clock: Clock,
+    token_principal_deltas: Deltas<DeltaBigInt>,
+    token_prices: StoreGetFloat64,
+    token_decimals: StoreGetBigInt,
+    float_add_store: StoreAddFloat64,
+) {
+    let ord = 0; // FOR NOW
 
-            let principal_token_address = submitted_bid_data.principal_token_address.clone();
-            let principal_amount = submitted_bid_data.principal_amount.clone();
+    let day_index = clock.timestamp.as_ref().unwrap().seconds / 86400;
 
-            let principal_amount_usdc = calculate_principal_amount_usdc(
-                principal_amount,
-                principal_token_address,
-                weth_address,
-                usdc_address,
-                &token_prices,
-                &token_decimals,
-            );
-            
-            
+    let weth_address = H160::from_str("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2").unwrap();
+    let usdc_address = H160::from_str("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").unwrap();
 
-            let principal_amount_usdc_big_decimal = f64_to_bigdecimal(principal_amount_usdc);
+    for delta in token_principal_deltas.iter() {
+        if substreams::key::segment_at(&delta.key, 0) != "token_principal" {
+            continue;
+        }
 
-            tables
-                .update_row("tellerv2_bid", bid_id.to_string())
-               
-                .set("status", "accepted".to_string())
-                .set("accepted_at", event_block_time )
-                .set(
-                    "borrower",
-                    Hex(&submitted_bid_data.borrower_address).to_string(),
-                )
-                .set(
-                    "lender",
-                    Hex(&submitted_bid_data.lender_address).to_string(),
-                )
-                // .set("receiver", Hex(&evt.receiver).to_string())
-                .set(
-                    "principal_token_address",
-                    Hex(&submitted_bid_data.principal_token_address).to_string(),
-                )
-                .set("principal_amount", &submitted_bid_data.principal_amount)
-                .set("principal_amount_usdc", &principal_amount_usdc_big_decimal);
+        let principal_token = substreams::key::segment_at(&delta.key, 1);
+        let flow_type = substreams::key::segment_at(&delta.key, 2);
+
+        let principal_change = delta.new_value.clone() - delta.old_value.clone();
+
+        let principal_change_usdc = calculate_principal_amount_usdc(
+            principal_change,
+            H160::from_str(principal_token).unwrap(),
+            weth_address,
+            usdc_address,
+            &token_prices,
+            &token_decimals,
+        );
+
+        float_add_store.add(
+            ord,
+            format!("protocol_principal_usdc:{}", flow_type),
+            principal_change_usdc,
+        );
+
+        if flow_type != "outstanding" {
+            float_add_store.add(
+                ord,
+                format!("protocol_principal_usdc_daily:{}:{}", day_index, flow_type),
+                principal_change_usdc,
+            );
         }
-    });
+    }
+}
 
-    events.tellerv2_cancelled_bids.iter().for_each(|evt| {
-        tables
-            .create_row(
-                "tellerv2_cancelled_bid",
-                format!("{}-{}", evt.evt_tx_hash, evt.evt_index),
-            )
-            .set("evt_tx_hash", &evt.evt_tx_hash)
-            .set("evt_index", evt.evt_index)
-            .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
-            .set("evt_block_number", evt.evt_block_number)
-            .set("bid_id", BigDecimal::from_str(&evt.bid_id).unwrap());
-            
-            
-                let bid_id = BigInt::from_str(&evt.bid_id).unwrap();
-                    
-                
-                  tables
-                .update_row("tellerv2_bid", bid_id.to_string())
-                  
-                .set("status", "cancelled".to_string())
-               ;
-             
-             
-                
+/*
+  Terms of accepted bids that have not been closed out yet, for the interest accrual and the default checks.
+  Refreshed from bids(uint256) after every repayment and dropped once the loan is repaid or liquidated.
+
+  Like store_pause_window this writes at evt_index + 1, so get_at(evt_index) gives the terms just before that log.
+  For a repayment that is the payment cycle due date it was settling.
+
+  active_bid:{bid_id}:{owed_principal|apr|last_repaid_timestamp|days_in_year|next_due_date|default_duration}
+*/
+#[substreams::handlers::store]
+fn store_active_bid_terms(
+    events: contract::Events,
+    bid_loan_details_list: teller::BidLoanDetailsList,
+    bigint_set_store: StoreSetBigInt,
+) {
+    let teller_v2_address = Address::from_slice(&TELLERV2_TRACKED_CONTRACT);
+
+    let bid_loan_details_by_id = bid_loan_details_by_id(&bid_loan_details_list);
+
+    // (evt_index, bid_id, accepted|repayment|closed)
+    let mut bid_term_changes: Vec<(u32, String, &str)> = Vec::new();
+
+    events.tellerv2_accepted_bids.iter().for_each(|evt| {
+        bid_term_changes.push((evt.evt_index, evt.bid_id.clone(), "accepted"));
     });
-    events.tellerv2_fee_paids.iter().for_each(|evt| {
-        tables
-            .create_row(
-                "tellerv2_fee_paid",
-                format!("{}-{}", evt.evt_tx_hash, evt.evt_index),
-            )
-            .set("evt_tx_hash", &evt.evt_tx_hash)
-            .set("evt_index", evt.evt_index)
-            .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
-            .set("evt_block_number", evt.evt_block_number)
-            .set("amount", BigDecimal::from_str(&evt.amount).unwrap())
-            .set("bid_id", BigDecimal::from_str(&evt.bid_id).unwrap())
-            .set("fee_type", &evt.fee_type);
+    events.tellerv2_loan_repayments.iter().for_each(|evt| {
+        bid_term_changes.push((evt.evt_index, evt.bid_id.clone(), "repayment"));
     });
-    events.tellerv2_initializeds.iter().for_each(|evt| {
-        tables
-            .create_row(
-                "tellerv2_initialized",
-                format!("{}-{}", evt.evt_tx_hash, evt.evt_index),
-            )
-            .set("evt_tx_hash", &evt.evt_tx_hash)
-            .set("evt_index", evt.evt_index)
-            .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
-            .set("evt_block_number", evt.evt_block_number)
-            .set("version", evt.version);
+    events.tellerv2_loan_repaids.iter().for_each(|evt| {
+        bid_term_changes.push((evt.evt_index, evt.bid_id.clone(), "closed"));
     });
     events.tellerv2_loan_liquidateds.iter().for_each(|evt| {
-        tables
-            .create_row(
-                "tellerv2_loan_liquidated",
-                format!("{}-{}", evt.evt_tx_hash, evt.evt_index),
-            )
-            .set("evt_tx_hash", &evt.evt_tx_hash)
-            .set("evt_index", evt.evt_index)
-            .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
-            .set("evt_block_number", evt.evt_block_number)
-            .set("bid_id", BigDecimal::from_str(&evt.bid_id).unwrap())
-            .set("liquidator", Hex(&evt.liquidator).to_string());
-            
-            
-                    let bid_id = BigInt::from_str(&evt.bid_id).unwrap();
-            
-                                                            
-                  
-                
-                tables
-                .update_row("tellerv2_bid", bid_id.to_string())
-                  
-                .set("status", "liquidated".to_string()) ;
-                
-            
-    });
-    events.tellerv2_loan_repaids.iter().for_each(|evt| {
-        tables
-            .create_row(
-                "tellerv2_loan_repaid",
-                format!("{}-{}", evt.evt_tx_hash, evt.evt_index),
-            )
-            .set("evt_tx_hash", &evt.evt_tx_hash)
-            .set("evt_index", evt.evt_index)
-            .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
-            .set("evt_block_number", evt.evt_block_number)
-            .set("bid_id", BigDecimal::from_str(&evt.bid_id).unwrap());
-            
-            
-                let bid_id = BigInt::from_str(&evt.bid_id).unwrap();
-                 
-                
-                tables
-                .update_row("tellerv2_bid", bid_id.to_string())
-                
-                .set("status", "repaid".to_string())
-               ;
-            
+        bid_term_changes.push((evt.evt_index, evt.bid_id.clone(), "closed"));
+    });
+
+    bid_term_changes.sort_by_key(|(evt_index, _, _)| *evt_index);
+
+    for (evt_index, bid_id, change) in bid_term_changes {
+        let ord = evt_index as u64 + 1;
+
+        if change == "closed" {
+            bigint_set_store.delete_prefix(ord as i64, &format!("active_bid:{}:", bid_id));
+            continue;
+        }
+
+        let bid_loan_details = match bid_loan_details_by_id.get(&bid_id) {
+            Some(bid_loan_details) => bid_loan_details,
+            None => continue,
+        };
+
+        let bid_id = BigInt::from_str(&bid_id).unwrap();
+
+        let owed_principal = bid_loan_details.principal_amount.clone()
+            - bid_loan_details.total_repaid_principal.clone();
+
+        // same fallback as V2Calculations.lastRepaidTimestamp
+        let last_repaid_timestamp = match bid_loan_details.last_repaid_timestamp.is_zero() {
+            true => bid_loan_details.accepted_timestamp.clone(),
+            false => bid_loan_details.last_repaid_timestamp.clone(),
+        };
+
+        let payment_cycle_type =
+            rpc::tellerv2::fetch_bid_payment_cycle_type_from_rpc(&teller_v2_address, &bid_id)
+                .unwrap_or(BigInt::zero());
+
+        let days_in_year = match payment_cycle_type.to_u64() {
+            1 => BigInt::from(360),
+            _ => BigInt::from(365),
+        };
+
+        let next_due_date =
+            rpc::tellerv2::fetch_bid_next_due_date_from_rpc(&teller_v2_address, &bid_id)
+                .unwrap_or(BigInt::zero());
+
+        bigint_set_store.set(
+            ord,
+            format!("active_bid:{}:owed_principal", bid_id),
+            &owed_principal,
+        );
+        bigint_set_store.set(
+            ord,
+            format!("active_bid:{}:apr", bid_id),
+            &bid_loan_details.apr,
+        );
+        bigint_set_store.set(
+            ord,
+            format!("active_bid:{}:last_repaid_timestamp", bid_id),
+            &last_repaid_timestamp,
+        );
+        bigint_set_store.set(
+            ord,
+            format!("active_bid:{}:days_in_year", bid_id),
+            &days_in_year,
+        );
+        bigint_set_store.set(
+            ord,
+            format!("active_bid:{}:next_due_date", bid_id),
+            &next_due_date,
+        );
+
+        // the default duration is fixed when the bid is submitted
+        if change == "accepted" {
+            let default_duration =
+                rpc::tellerv2::fetch_bid_default_duration_from_rpc(&teller_v2_address, &bid_id)
+                    .unwrap_or(BigInt::zero());
+
+            bigint_set_store.set(
+                ord,
+                format!("active_bid:{}:default_duration", bid_id),
+                &default_duration,
+            );
+        }
+    }
+}
+
+/*
+  Borrower and vintage of every open loan, for the defaults that are found from the clock rather than from an event.
+
+  active_bid:{bid_id}:account -> {borrower}:{market_id}:{principal_token}:{origination month index}
+*/
+#[substreams::handlers::store]
+fn store_active_bid_accounts(
+    events: contract::Events,
+    bid_loan_details_list: teller::BidLoanDetailsList,
+    string_set_store: StoreSetString,
+) {
+    let ord = 0; // FOR NOW
+
+    let bid_loan_details_by_id = bid_loan_details_by_id(&bid_loan_details_list);
+
+    events.tellerv2_accepted_bids.iter().for_each(|evt| {
+        if let Some(bid_loan_details) = bid_loan_details_by_id.get(&evt.bid_id) {
+            string_set_store.set(
+                ord,
+                format!("active_bid:{}:account", evt.bid_id),
+                &format!(
+                    "{}:{}",
+                    address_to_string(&bid_loan_details.borrower_address),
+                    vintage_key_for_bid(bid_loan_details)
+                ),
+            );
+        }
+    });
+
+    events.tellerv2_loan_repaids.iter().for_each(|evt| {
+        string_set_store.delete_prefix(ord as i64, &format!("active_bid:{}:", evt.bid_id));
+    });
+    events.tellerv2_loan_liquidateds.iter().for_each(|evt| {
+        string_set_store.delete_prefix(ord as i64, &format!("active_bid:{}:", evt.bid_id));
+    });
+}
+
+/*
+  Longest a payment on the bid came in after the payment cycle due date it was settling, in seconds.
+
+  bid_delinquency:{borrower}:{bid_id}
+*/
+#[substreams::handlers::store]
+fn store_bid_delinquency(
+    events: contract::Events,
+    bid_loan_details_list: teller::BidLoanDetailsList,
+    active_bid_terms: StoreGetBigInt,
+    bigint_max_store: StoreMaxBigInt,
+) {
+    let ord = 0; // FOR NOW
+
+    let bid_loan_details_by_id = bid_loan_details_by_id(&bid_loan_details_list);
+
+    for (evt_index, evt_block_time, bid_id) in repayment_events(&events) {
+        let bid_loan_details = match bid_loan_details_by_id.get(bid_id) {
+            Some(bid_loan_details) => bid_loan_details,
+            None => continue,
+        };
+
+        let next_due_date = match active_bid_terms
+            .get_at(evt_index as u64, format!("active_bid:{}:next_due_date", bid_id))
+        {
+            Some(next_due_date) if !next_due_date.is_zero() => next_due_date.to_u64() as i64,
+            _ => continue,
+        };
+
+        bigint_max_store.max(
+            ord,
+            format!(
+                "bid_delinquency:{}:{}",
+                address_to_string(&bid_loan_details.borrower_address),
+                bid_id
+            ),
+            BigInt::from((evt_block_time - next_due_date).max(0)),
+        );
+    }
+}
+
+/*
+  Every loan that went into default, set once at the first block it is seen past its default timestamp.
+
+  TellerV2 counts a loan as defaulted once block.timestamp > calculateNextDueDate + bidDefaultDuration, it emits no
+  event for it.  Payments are checked against the terms just before them and every open loan is checked against the
+  clock at each day boundary, so a loan that defaults and is never closed shows up at most a day late.
+  A liquidation always counts as a default.
+
+  loan_default:{bid_id} -> {delinquency_seconds}:{borrower}:{market_id}:{principal_token}:{origination month index}
+*/
+#[substreams::handlers::store]
+fn store_loan_defaults(
+    clock: Clock,
+    events: contract::Events,
+    bid_loan_details_list: teller::BidLoanDetailsList,
+    day_index_deltas: Deltas<DeltaBigInt>,
+    active_bid_buckets: StoreGetBigInt,
+    latest_accepted_bid_id: StoreGetBigInt,
+    active_bid_terms: StoreGetBigInt,
+    active_bid_accounts: StoreGetString,
+    string_set_if_not_exists_store: StoreSetIfNotExistsString,
+) {
+    let ord = 0; // FOR NOW
+
+    let bid_loan_details_by_id = bid_loan_details_by_id(&bid_loan_details_list);
+
+    let set_loan_default = |bid_id: &String, delinquency_seconds: i64, bid_account: &String| {
+        string_set_if_not_exists_store.set_if_not_exists(
+            ord,
+            format!("loan_default:{}", bid_id),
+            &format!("{}:{}", delinquency_seconds, bid_account),
+        );
+    };
+
+    let bid_account_for = |bid_loan_details: &rpc::tellerv2::BidLoanDetailsData| {
+        format!(
+            "{}:{}",
+            address_to_string(&bid_loan_details.borrower_address),
+            vintage_key_for_bid(bid_loan_details)
+        )
+    };
+
+    for (evt_index, evt_block_time, bid_id) in repayment_events(&events) {
+        let bid_loan_details = match bid_loan_details_by_id.get(bid_id) {
+            Some(bid_loan_details) => bid_loan_details,
+            None => continue,
+        };
+
+        let get_term = |term: &str| {
+            active_bid_terms
+                .get_at(evt_index as u64, format!("active_bid:{}:{}", bid_id, term))
+                .map_or(0, |value| value.to_u64() as i64)
+        };
+
+        let next_due_date = get_term("next_due_date");
+
+        if is_loan_defaulted_at(evt_block_time, next_due_date, get_term("default_duration")) {
+            set_loan_default(bid_id, evt_block_time - next_due_date, &bid_account_for(bid_loan_details));
+        }
+    }
+
+    events.tellerv2_loan_liquidateds.iter().for_each(|evt| {
+        if let Some(bid_loan_details) = bid_loan_details_by_id.get(&evt.bid_id) {
+            let next_due_date = active_bid_terms
+                .get_at(evt.evt_index as u64, format!("active_bid:{}:next_due_date", evt.bid_id))
+                .map_or(0, |value| value.to_u64() as i64);
+
+            let delinquency_seconds = match next_due_date {
+                0 => 0,
+                _ => (evt.evt_block_time.as_ref().unwrap().seconds - next_due_date).max(0),
+            };
+
+            set_loan_default(&evt.bid_id, delinquency_seconds, &bid_account_for(bid_loan_details));
+        }
+    });
+
+    let block_seconds = clock.timestamp.as_ref().unwrap().seconds;
+
+    for day_index_delta in day_index_deltas.iter() {
+        if day_index_delta.old_value.is_zero() || day_index_delta.old_value == day_index_delta.new_value {
+            continue;
+        }
+
+        for bid_id in open_bid_ids(&active_bid_buckets, &latest_accepted_bid_id) {
+            let get_term = |term: &str| {
+                active_bid_terms
+                    .get_last(format!("active_bid:{}:{}", bid_id, term))
+                    .map_or(0, |value| value.to_u64() as i64)
+            };
+
+            let next_due_date = get_term("next_due_date");
+
+            if !is_loan_defaulted_at(block_seconds, next_due_date, get_term("default_duration")) {
+                continue;
+            }
+
+            if let Some(bid_account) =
+                active_bid_accounts.get_last(format!("active_bid:{}:account", bid_id))
+            {
+                set_loan_default(&bid_id, block_seconds - next_due_date, &bid_account);
+            }
+        }
+    }
+}
+
+/*
+  LoanRepayment and LoanRepaid as (evt_index, block time, bid id), a full repayment is the last payment of the loan.
+*/
+fn repayment_events(events: &contract::Events) -> Vec<(u32, i64, &String)> {
+    events
+        .tellerv2_loan_repayments
+        .iter()
+        .map(|evt| (evt.evt_index, evt.evt_block_time.as_ref().unwrap().seconds, &evt.bid_id))
+        .chain(
+            events
+                .tellerv2_loan_repaids
+                .iter()
+                .map(|evt| (evt.evt_index, evt.evt_block_time.as_ref().unwrap().seconds, &evt.bid_id)),
+        )
+        .collect()
+}
+
+// bid ids are sequential, the accrual walks them in buckets of this many ids
+const ACTIVE_BID_BUCKET_SIZE: u64 = 100;
+
+/*
+  Number of open loans per bucket of bid ids.  Stores cannot be iterated so this is how the accrual finds the bids to
+  look at, it skips every bucket that has no open loan left instead of reading every bid ever accepted.
+
+  active_bid_bucket:{bid_id / ACTIVE_BID_BUCKET_SIZE}
+*/
+#[substreams::handlers::store]
+fn store_active_bid_buckets(events: contract::Events, bigint_add_store: StoreAddBigInt) {
+    let ord = 0; // FOR NOW
+
+    let bucket_key = |bid_id: &String| {
+        format!(
+            "active_bid_bucket:{}",
+            u64::from_str(bid_id).unwrap() / ACTIVE_BID_BUCKET_SIZE
+        )
+    };
+
+    events.tellerv2_accepted_bids.iter().for_each(|evt| {
+        bigint_add_store.add(ord, bucket_key(&evt.bid_id), BigInt::one());
+    });
+
+    // liquidateLoanFull emits both LoanRepaid and LoanLiquidated, the bid closes once
+    let mut closed_bid_ids: BTreeSet<String> = BTreeSet::new();
+
+    events.tellerv2_loan_repaids.iter().for_each(|evt| {
+        closed_bid_ids.insert(evt.bid_id.clone());
+    });
+    events.tellerv2_loan_liquidateds.iter().for_each(|evt| {
+        closed_bid_ids.insert(evt.bid_id.clone());
+    });
+
+    for bid_id in closed_bid_ids {
+        bigint_add_store.add(ord, bucket_key(&bid_id), BigInt::from(-1));
+    }
+}
+
+/*
+  Highest accepted bid id, the last bucket the accrual has to look at.
+*/
+#[substreams::handlers::store]
+fn store_latest_accepted_bid_id(events: contract::Events, bigint_max_store: StoreMaxBigInt) {
+    let ord = 0; // FOR NOW
+
+    events.tellerv2_accepted_bids.iter().for_each(|evt| {
+        bigint_max_store.max(
+            ord,
+            "latest_accepted_bid_id",
+            BigInt::from_str(&evt.bid_id).unwrap(),
+        );
+    });
+}
+
+/*
+  Candidate ids of every open loan: all ids in the buckets that still have an open loan.
+  Closed bids in those buckets have no terms left in store_active_bid_terms.
+*/
+fn open_bid_ids(active_bid_buckets: &StoreGetBigInt, latest_accepted_bid_id: &StoreGetBigInt) -> Vec<String> {
+    let latest_bucket = match latest_accepted_bid_id.get_last("latest_accepted_bid_id") {
+        Some(latest_accepted_bid_id) => latest_accepted_bid_id.to_u64() / ACTIVE_BID_BUCKET_SIZE,
+        None => return Vec::new(),
+    };
+
+    (0..=latest_bucket)
+        .filter(|bucket| {
+            active_bid_buckets
+                .get_last(format!("active_bid_bucket:{}", bucket))
+                .map_or(false, |open_loans| open_loans > BigInt::zero())
+        })
+        .flat_map(|bucket| {
+            (bucket * ACTIVE_BID_BUCKET_SIZE..(bucket + 1) * ACTIVE_BID_BUCKET_SIZE)
+                .map(|bid_id| bid_id.to_string())
+        })
+        .collect()
+}
+
+/*
+  Day index of the latest block.  Downstream modules read the deltas, a delta whose old and new values differ marks a day boundary.
+*/
+#[substreams::handlers::store]
+fn store_day_index(clock: Clock, bigint_set_store: StoreSetBigInt) {
+    let ord = 0; // FOR NOW
+
+    let day_index = clock.timestamp.as_ref().unwrap().seconds / 86400;
+
+    bigint_set_store.set(ord, "day_index", &BigInt::from(day_index));
+}
+
+/*
+  Hour index of the latest block, read as deltas the same way as store_day_index.
+*/
+#[substreams::handlers::store]
+fn store_hour_index(clock: Clock, bigint_set_store: StoreSetBigInt) {
+    let ord = 0; // FOR NOW
+
+    let hour_index = clock.timestamp.as_ref().unwrap().seconds / 3600;
+
+    bigint_set_store.set(ord, "hour_index", &BigInt::from(hour_index));
+}
+
+// window period name and its length in seconds
+const ACTIVITY_WINDOW_PERIODS: [(&str, i64); 2] = [("hour", 3600), ("day", 86400)];
+
+/*
+  TellerV2 event counts per hour and day window.
+
+  activity_window:{hour|day}:{window_index}:{submitted_bids|accepted_bids|repayments|liquidations|fee_payments}
+*/
+#[substreams::handlers::store]
+fn store_activity_window_counts(clock: Clock, events: contract::Events, bigint_add_store: StoreAddBigInt) {
+    let ord = 0; // FOR NOW
+
+    let block_seconds = clock.timestamp.as_ref().unwrap().seconds;
+
+    // liquidateLoanFull emits LoanRepaid before LoanLiquidated, that LoanRepaid is the liquidation and not a repayment
+    let liquidated_bid_ids: BTreeSet<&String> = events
+        .tellerv2_loan_liquidateds
+        .iter()
+        .map(|evt| &evt.bid_id)
+        .collect();
+    let full_repayments = events
+        .tellerv2_loan_repaids
+        .iter()
+        .filter(|evt| !liquidated_bid_ids.contains(&evt.bid_id))
+        .count();
+
+    // a full repayment emits LoanRepaid instead of LoanRepayment, both count as a repayment
+    let event_counts = [
+        ("submitted_bids", events.tellerv2_submitted_bids.len()),
+        ("accepted_bids", events.tellerv2_accepted_bids.len()),
+        ("repayments", events.tellerv2_loan_repayments.len() + full_repayments),
+        ("liquidations", events.tellerv2_loan_liquidateds.len()),
+        ("fee_payments", events.tellerv2_fee_paids.len()),
+    ];
+
+    for (period, period_seconds) in ACTIVITY_WINDOW_PERIODS {
+        let window_index = block_seconds / period_seconds;
+
+        for (field, count) in event_counts {
+            if count == 0 {
+                continue;
+            }
+
+            bigint_add_store.add(
+                ord,
+                format!("activity_window:{}:{}:{}", period, window_index, field),
+                BigInt::from(count as u64),
+            );
+        }
+    }
+}
+
+/*
+  TellerV2 value flows per hour and day window, in USDC at the price of the block they happened in.
+
+  activity_window_usdc:{hour|day}:{window_index}:{originated|repaid_principal|repaid_interest|liquidated|fees}
+*/
+#[substreams::handlers::store]
+fn store_activity_window_usdc(
+    clock: Clock,
+    events: contract::Events,
+    bid_loan_details_list: teller::BidLoanDetailsList,
+    token_principal_deltas: Deltas<DeltaBigInt>,
+    bid_repaid_totals_deltas: Deltas<DeltaBigInt>,
+    token_prices: StoreGetFloat64,
+    token_decimals: StoreGetBigInt,
+    float_add_store: StoreAddFloat64,
+) {
+    let ord = 0; // FOR NOW
+
+    let block_seconds = clock.timestamp.as_ref().unwrap().seconds;
+
+    let bid_loan_details_by_id = bid_loan_details_by_id(&bid_loan_details_list);
+
+    let weth_address = H160::from_str("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2").unwrap();
+    let usdc_address = H160::from_str("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").unwrap();
+
+    let mut flows_usdc: Vec<(&str, f64)> = Vec::new();
+
+    let mut to_usdc = |flow: &'static str, amount: BigInt, token_address: &str| {
+        flows_usdc.push((
+            flow,
+            calculate_principal_amount_usdc(
+                amount,
+                H160::from_str(token_address).unwrap(),
+                weth_address,
+                usdc_address,
+                &token_prices,
+                &token_decimals,
+            ),
+        ));
+    };
+
+    // principal flows are already split into originated, repaid and liquidated by store_token_principal
+    for delta in token_principal_deltas.iter() {
+        if substreams::key::segment_at(&delta.key, 0) != "token_principal" {
+            continue;
+        }
+
+        let flow = match substreams::key::segment_at(&delta.key, 2) {
+            "originated" => "originated",
+            "repaid" => "repaid_principal",
+            "liquidated" => "liquidated",
+            _ => continue,
+        };
+
+        to_usdc(
+            flow,
+            delta.new_value.clone() - delta.old_value.clone(),
+            substreams::key::segment_at(&delta.key, 1),
+        );
+    }
+
+    for delta in bid_repaid_totals_deltas.iter() {
+        if substreams::key::segment_at(&delta.key, 0) != "bid_repaid_interest" {
+            continue;
+        }
+
+        let interest_repaid = delta.new_value.clone() - delta.old_value.clone();
+
+        if interest_repaid <= BigInt::zero() {
+            continue;
+        }
+
+        to_usdc(
+            "repaid_interest",
+            interest_repaid,
+            substreams::key::segment_at(&delta.key, 1),
+        );
+    }
+
+    // fees are paid out of the principal token of the bid
+    events.tellerv2_fee_paids.iter().for_each(|evt| {
+        if let Some(bid_loan_details) = bid_loan_details_by_id.get(&evt.bid_id) {
+            to_usdc(
+                "fees",
+                BigInt::from_str(&evt.amount).unwrap(),
+                &address_to_string(&bid_loan_details.principal_token_address),
+            );
+        }
+    });
+
+    for (period, period_seconds) in ACTIVITY_WINDOW_PERIODS {
+        let window_index = block_seconds / period_seconds;
+
+        for (flow, amount_usdc) in flows_usdc.iter() {
+            float_add_store.add(
+                ord,
+                format!("activity_window_usdc:{}:{}:{}", period, window_index, flow),
+                *amount_usdc,
+            );
+        }
+    }
+}
+
+struct BidClosure {
+    bid_id: String,
+
+    evt_index: u32,
+
+    borrower: String,
+
+    liquidated: bool,
+
+    vintage_key: String,
+}
+
+/*
+  liquidateLoanFull also goes through _repayLoan so every closed loan shows up as a LoanRepaid event.
+*/
+fn fetch_bid_closures(
+    events: &contract::Events,
+    bid_loan_details_by_id: &BTreeMap<String, rpc::tellerv2::BidLoanDetailsData>,
+) -> Vec<BidClosure> {
+    events
+        .tellerv2_loan_repaids
+        .iter()
+        .filter_map(|evt| {
+            let bid_loan_details = bid_loan_details_by_id.get(&evt.bid_id)?;
+
+            let liquidated = events
+                .tellerv2_loan_liquidateds
+                .iter()
+                .any(|liquidated_evt| liquidated_evt.bid_id == evt.bid_id);
+
+            Some(BidClosure {
+                bid_id: evt.bid_id.clone(),
+                evt_index: evt.evt_index,
+                borrower: address_to_string(&bid_loan_details.borrower_address),
+                liquidated,
+                vintage_key: vintage_key_for_bid(bid_loan_details),
+            })
+        })
+        .collect()
+}
+
+const BORROWER_CREDIT_FIELDS: [&str; 5] = [
+    "loans_accepted",
+    "loans_repaid_on_time",
+    "loans_repaid_late",
+    "loans_defaulted",
+    "loans_liquidated",
+];
+
+/*
+  Adds zero to every other count of the borrower, same as add_to_vintage, so graph_out always gets the full set of counts.
+*/
+fn add_to_borrower_credit(bigint_add_store: &StoreAddBigInt, borrower: &str, field: &str, amount: &BigInt) {
+    let ord = 0; // FOR NOW
+
+    for credit_field in BORROWER_CREDIT_FIELDS {
+        let value = match credit_field == field {
+            true => amount.clone(),
+            false => BigInt::zero(),
+        };
+
+        bigint_add_store.add(ord, format!("borrower_credit:{}:{}", borrower, credit_field), value);
+    }
+}
+
+/*
+  Per borrower loan counts.  Defaults are counted when the loan defaults, from store_loan_defaults, so a closed loan
+  is only counted as repaid on time or late when it never defaulted.
+
+  borrower_credit:{borrower}:{loans_accepted|loans_repaid_on_time|loans_repaid_late|loans_defaulted|loans_liquidated}
+*/
+#[substreams::handlers::store]
+fn store_borrower_credit_history(
+    events: contract::Events,
+    bid_loan_details_list: teller::BidLoanDetailsList,
+    active_bid_terms: StoreGetBigInt,
+    bid_delinquency: StoreGetBigInt,
+    loan_defaults_deltas: Deltas<DeltaString>,
+    bigint_add_store: StoreAddBigInt,
+) {
+    let bid_loan_details_by_id = bid_loan_details_by_id(&bid_loan_details_list);
+
+    events.tellerv2_accepted_bids.iter().for_each(|evt| {
+        if let Some(bid_loan_details) = bid_loan_details_by_id.get(&evt.bid_id) {
+            add_to_borrower_credit(
+                &bigint_add_store,
+                &address_to_string(&bid_loan_details.borrower_address),
+                "loans_accepted",
+                &BigInt::one(),
+            );
+        }
+    });
+
+    for delta in loan_defaults_deltas.iter() {
+        add_to_borrower_credit(
+            &bigint_add_store,
+            substreams::key::segment_at(&delta.new_value, 1),
+            "loans_defaulted",
+            &BigInt::one(),
+        );
+    }
+
+    for bid_closure in fetch_bid_closures(&events, &bid_loan_details_by_id) {
+        let longest_delinquency_seconds = bid_delinquency
+            .get_last(format!(
+                "bid_delinquency:{}:{}",
+                bid_closure.borrower, bid_closure.bid_id
+            ))
+            .map_or(0, |value| value.to_u64() as i64);
+
+        let default_duration = active_bid_terms
+            .get_at(
+                bid_closure.evt_index as u64,
+                format!("active_bid:{}:default_duration", bid_closure.bid_id),
+            )
+            .map_or(0, |value| value.to_u64() as i64);
+
+        // a loan that defaulted was already counted when it defaulted
+        let (credit_outcome, count) = match classify_loan_closure(
+            longest_delinquency_seconds,
+            default_duration,
+            bid_closure.liquidated,
+        ) {
+            Some(credit_outcome) => (credit_outcome, BigInt::one()),
+            None => ("loans_defaulted", BigInt::zero()),
+        };
+
+        add_to_borrower_credit(&bigint_add_store, &bid_closure.borrower, credit_outcome, &count);
+
+        if bid_closure.liquidated {
+            add_to_borrower_credit(
+                &bigint_add_store,
+                &bid_closure.borrower,
+                "loans_liquidated",
+                &BigInt::one(),
+            );
+        }
+    }
+}
+
+/*
+  Longest delinquency over every loan of the borrower, open defaulted loans included.
+*/
+#[substreams::handlers::store]
+fn store_borrower_longest_delinquency(
+    bid_delinquency_deltas: Deltas<DeltaBigInt>,
+    loan_defaults_deltas: Deltas<DeltaString>,
+    bigint_max_store: StoreMaxBigInt,
+) {
+    let ord = 0; // FOR NOW
+
+    for delta in bid_delinquency_deltas.iter() {
+        bigint_max_store.max(
+            ord,
+            format!(
+                "borrower_credit:{}:longest_delinquency_seconds",
+                substreams::key::segment_at(&delta.key, 1)
+            ),
+            &delta.new_value,
+        );
+    }
+
+    for delta in loan_defaults_deltas.iter() {
+        bigint_max_store.max(
+            ord,
+            format!(
+                "borrower_credit:{}:longest_delinquency_seconds",
+                substreams::key::segment_at(&delta.new_value, 1)
+            ),
+            BigInt::from_str(substreams::key::segment_at(&delta.new_value, 0)).unwrap(),
+        );
+    }
+}
+
+#[substreams::handlers::store]
+fn store_borrower_borrowed_usdc(
+    events: contract::Events,
+    bid_loan_details_list: teller::BidLoanDetailsList,
+    token_prices: StoreGetFloat64,
+    token_decimals: StoreGetBigInt,
+    float_add_store: StoreAddFloat64,
+) {
+    let ord = 0; // FOR NOW
+
+    let bid_loan_details_by_id = bid_loan_details_by_id(&bid_loan_details_list);
+
+    let weth_address = H160::from_str("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2").unwrap();
+    let usdc_address = H160::from_str("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").unwrap();
+
+    events.tellerv2_accepted_bids.iter().for_each(|evt| {
+        if let Some(bid_loan_details) = bid_loan_details_by_id.get(&evt.bid_id) {
+            let principal_amount_usdc = calculate_principal_amount_usdc(
+                bid_loan_details.principal_amount.clone(),
+                bid_loan_details.principal_token_address,
+                weth_address,
+                usdc_address,
+                &token_prices,
+                &token_decimals,
+            );
+
+            float_add_store.add(
+                ord,
+                format!(
+                    "borrower_credit:{}:total_borrowed_usdc",
+                    address_to_string(&bid_loan_details.borrower_address)
+                ),
+                principal_amount_usdc,
+            );
+        }
+    });
+}
+
+const VINTAGE_FIELDS: [&str; 7] = [
+    "loans_originated",
+    "principal_originated",
+    "principal_repaid",
+    "interest_repaid",
+    "loans_defaulted",
+    "loans_liquidated",
+    "principal_repaid_by_liquidator",
+];
+
+/*
+  {market_id}:{principal_token}:{origination month index}
+*/
+fn vintage_key_for_bid(bid_loan_details: &rpc::tellerv2::BidLoanDetailsData) -> String {
+    format!(
+        "{}:{}:{}",
+        bid_loan_details.market_id,
+        address_to_string(&bid_loan_details.principal_token_address),
+        month_index_from_timestamp(bid_loan_details.accepted_timestamp.to_u64() as i64)
+    )
+}
+
+/*
+  Adds zero to every other vintage field so that the deltas always carry the full vintage and graph_out can write complete rows.
+*/
+fn add_to_vintage(bigint_add_store: &StoreAddBigInt, vintage_key: &str, field: &str, amount: &BigInt) {
+    let ord = 0; // FOR NOW
+
+    for vintage_field in VINTAGE_FIELDS {
+        let value = match vintage_field == field {
+            true => amount.clone(),
+            false => BigInt::zero(),
+        };
+
+        bigint_add_store.add(ord, format!("vintage:{}:{}", vintage_key, vintage_field), value);
+    }
+}
+
+// TellerV2 went live in July 2022, no vintage is older than that
+const FIRST_VINTAGE_TIMESTAMP: i64 = 1657426010;
+
+/*
+  Month index of the latest block, read as deltas the same way as store_day_index.
+*/
+#[substreams::handlers::store]
+fn store_month_index(clock: Clock, bigint_set_store: StoreSetBigInt) {
+    let ord = 0; // FOR NOW
+
+    let month_index = month_index_from_timestamp(clock.timestamp.as_ref().unwrap().seconds);
+
+    bigint_set_store.set(ord, "month_index", &BigInt::from(month_index));
+}
+
+/*
+  Loans of each vintage that are still open.  A vintage keeps getting a row every month until all of its loans are closed.
+
+  vintage_open_loans:{market_id}:{principal_token}:{month_index}
+*/
+#[substreams::handlers::store]
+fn store_vintage_open_loans(
+    events: contract::Events,
+    bid_loan_details_list: teller::BidLoanDetailsList,
+    bigint_add_store: StoreAddBigInt,
+) {
+    let ord = 0; // FOR NOW
+
+    let bid_loan_details_by_id = bid_loan_details_by_id(&bid_loan_details_list);
+
+    events.tellerv2_accepted_bids.iter().for_each(|evt| {
+        if let Some(bid_loan_details) = bid_loan_details_by_id.get(&evt.bid_id) {
+            bigint_add_store.add(
+                ord,
+                format!("vintage_open_loans:{}", vintage_key_for_bid(bid_loan_details)),
+                BigInt::one(),
+            );
+        }
+    });
+
+    // liquidateLoanFull emits both LoanRepaid and LoanLiquidated, the loan closes once
+    let mut closed_bid_ids: BTreeSet<String> = BTreeSet::new();
+
+    events.tellerv2_loan_repaids.iter().for_each(|evt| {
+        closed_bid_ids.insert(evt.bid_id.clone());
+    });
+    events.tellerv2_loan_liquidateds.iter().for_each(|evt| {
+        closed_bid_ids.insert(evt.bid_id.clone());
+    });
+
+    for bid_id in closed_bid_ids {
+        if let Some(bid_loan_details) = bid_loan_details_by_id.get(&bid_id) {
+            bigint_add_store.add(
+                ord,
+                format!("vintage_open_loans:{}", vintage_key_for_bid(bid_loan_details)),
+                BigInt::from(-1),
+            );
+        }
+    }
+}
+
+/*
+  {market_id}:{principal_token} of every vintage of an origination month, appended once when the vintage gets its first loan.
+
+  vintage_index:{month_index}
+*/
+#[substreams::handlers::store]
+fn store_vintage_index(vintage_open_loans_deltas: Deltas<DeltaBigInt>, string_append_store: StoreAppend<String>) {
+    let ord = 0; // FOR NOW
+
+    for delta in vintage_open_loans_deltas.iter() {
+        if delta.operation != substreams::pb::substreams::store_delta::Operation::Create {
+            continue;
+        }
+
+        string_append_store.append(
+            ord,
+            format!("vintage_index:{}", substreams::key::segment_at(&delta.key, 3)),
+            format!(
+                "{}:{}",
+                substreams::key::segment_at(&delta.key, 1),
+                substreams::key::segment_at(&delta.key, 2)
+            ),
+        );
+    }
+}
+
+/*
+  Loss curve inputs per monthly origination vintage, per market and principal token.
+
+  vintage:{market_id}:{principal_token}:{month_index}:{field}
+*/
+#[substreams::handlers::store]
+fn store_vintage_totals(
+    events: contract::Events,
+    bid_loan_details_list: teller::BidLoanDetailsList,
+    bid_repaid_totals_deltas: Deltas<DeltaBigInt>,
+    loan_defaults_deltas: Deltas<DeltaString>,
+    month_index_deltas: Deltas<DeltaBigInt>,
+    vintage_index: StoreGetArray<String>,
+    vintage_open_loans: StoreGetBigInt,
+    bigint_add_store: StoreAddBigInt,
+) {
+    let bid_loan_details_by_id = bid_loan_details_by_id(&bid_loan_details_list);
+
+    // a new month rolls every open vintage forward so each one gets a vintage_month row for it, activity or not
+    for month_index_delta in month_index_deltas.iter() {
+        if month_index_delta.old_value.is_zero() || month_index_delta.old_value == month_index_delta.new_value {
+            continue;
+        }
+
+        let current_month_index = month_index_delta.new_value.to_u64() as i64;
+
+        for vintage_month_index in month_index_from_timestamp(FIRST_VINTAGE_TIMESTAMP)..=current_month_index {
+            let markets_and_tokens = vintage_index
+                .get_last(format!("vintage_index:{}", vintage_month_index))
+                .unwrap_or_default();
+
+            for market_and_token in markets_and_tokens {
+                let vintage_key = format!("{}:{}", market_and_token, vintage_month_index);
+
+                let open_loans = vintage_open_loans
+                    .get_last(format!("vintage_open_loans:{}", vintage_key))
+                    .unwrap_or(BigInt::zero());
+
+                if open_loans > BigInt::zero() {
+                    add_to_vintage(&bigint_add_store, &vintage_key, "loans_originated", &BigInt::zero());
+                }
+            }
+        }
+    }
+
+    events.tellerv2_accepted_bids.iter().for_each(|evt| {
+        if let Some(bid_loan_details) = bid_loan_details_by_id.get(&evt.bid_id) {
+            let vintage_key = vintage_key_for_bid(bid_loan_details);
+
+            add_to_vintage(&bigint_add_store, &vintage_key, "loans_originated", &BigInt::one());
+            add_to_vintage(
+                &bigint_add_store,
+                &vintage_key,
+                "principal_originated",
+                &bid_loan_details.principal_amount,
+            );
+        }
+    });
+
+    for delta in bid_repaid_totals_deltas.iter() {
+        let repaid_type = substreams::key::segment_at(&delta.key, 0);
+        let bid_id = substreams::key::segment_at(&delta.key, 2);
+
+        let amount_returned = delta.new_value.clone() - delta.old_value.clone();
+
+        if amount_returned <= BigInt::zero() {
+            continue;
+        }
+
+        let bid_loan_details = match bid_loan_details_by_id.get(bid_id) {
+            Some(bid_loan_details) => bid_loan_details,
+            None => continue,
+        };
+
+        let was_liquidated = events
+            .tellerv2_loan_liquidateds
+            .iter()
+            .any(|evt| evt.bid_id == bid_id);
+
+        let field = match (repaid_type, was_liquidated) {
+            ("bid_repaid_interest", _) => "interest_repaid",
+            (_, true) => "principal_repaid_by_liquidator",
+            (_, false) => "principal_repaid",
+        };
+
+        add_to_vintage(
+            &bigint_add_store,
+            &vintage_key_for_bid(bid_loan_details),
+            field,
+            &amount_returned,
+        );
+    }
+
+    // {delinquency_seconds}:{borrower}:{vintage key}
+    for delta in loan_defaults_deltas.iter() {
+        if let Some(vintage_key) = delta.new_value.splitn(3, ':').nth(2) {
+            add_to_vintage(&bigint_add_store, vintage_key, "loans_defaulted", &BigInt::one());
+        }
+    }
+
+    for bid_closure in fetch_bid_closures(&events, &bid_loan_details_by_id) {
+        if bid_closure.liquidated {
+            add_to_vintage(
+                &bigint_add_store,
+                &bid_closure.vintage_key,
+                "loans_liquidated",
+                &BigInt::one(),
+            );
+        }
+    }
+}
+
+/*
+  The pause window that is currently open, as {started_at_seconds}:{window_id}, empty once unpaused.
+  The window id is the id of the tellerv2_paused row that opened it.
+
+  Unlike the other stores this one writes at evt_index + 1 so that get_at(evt_index) in graph_out
+  gives the state just before that log, which lets events in the same block as a pause be checked.
+*/
+#[substreams::handlers::store]
+fn store_pause_window(events: contract::Events, string_set_store: StoreSetString) {
+    let mut pause_state_changes: Vec<(u32, String)> = Vec::new();
+
+    events.tellerv2_pauseds.iter().for_each(|evt| {
+        pause_state_changes.push((
+            evt.evt_index,
+            format!(
+                "{}:{}-{}",
+                evt.evt_block_time.as_ref().unwrap().seconds,
+                evt.evt_tx_hash,
+                evt.evt_index
+            ),
+        ));
+    });
+    events.tellerv2_unpauseds.iter().for_each(|evt| {
+        pause_state_changes.push((evt.evt_index, "".to_string()));
+    });
+
+    pause_state_changes.sort_by_key(|(evt_index, _)| *evt_index);
+
+    for (evt_index, open_pause_window) in pause_state_changes {
+        string_set_store.set(evt_index as u64 + 1, "open_pause_window", &open_pause_window);
+    }
+}
+
+/*
+  Returns the id of the pause window open just before the given log, if any.
+*/
+fn open_pause_window_at(pause_window_store: &StoreGetString, evt_index: u32) -> Option<(i64, String)> {
+    let open_pause_window = pause_window_store.get_at(evt_index as u64, "open_pause_window")?;
+
+    if open_pause_window.is_empty() {
+        return None;
+    }
+
+    let started_at = substreams::key::segment_at(&open_pause_window, 0).parse().unwrap();
+    let window_id = substreams::key::segment_at(&open_pause_window, 1).to_string();
+
+    Some((started_at, window_id))
+}
+
+/*
+  Accepted principal summed from AcceptedBid, the indexer side of lenderVolumeFilled / totalVolumeFilled.
+
+  lender_volume_filled:{principal_token}:{lender}
+  total_volume_filled:{principal_token}
+*/
+#[substreams::handlers::store]
+fn store_volume_filled(
+    events: contract::Events,
+    bid_loan_details_list: teller::BidLoanDetailsList,
+    bigint_add_store: StoreAddBigInt,
+) {
+    let ord = 0; // FOR NOW
+
+    let bid_loan_details_by_id = bid_loan_details_by_id(&bid_loan_details_list);
+
+    events.tellerv2_accepted_bids.iter().for_each(|evt| {
+        if let Some(bid_loan_details) = bid_loan_details_by_id.get(&evt.bid_id) {
+            let principal_token = address_to_string(&bid_loan_details.principal_token_address);
+
+            bigint_add_store.add(
+                ord,
+                format!(
+                    "lender_volume_filled:{}:{}",
+                    principal_token,
+                    address_to_string(&Address::from_slice(&evt.lender))
+                ),
+                &bid_loan_details.principal_amount,
+            );
+            bigint_add_store.add(
+                ord,
+                format!("total_volume_filled:{}", principal_token),
+                &bid_loan_details.principal_amount,
+            );
+        }
+    });
+}
+
+/*
+  Contract counters minus indexed counters, taken in the block where the indexer first sees each counter,
+  so volume filled before the initial block doesn't show up as a mismatch.
+
+  lender_volume_filled_baseline:{principal_token}:{lender}
+  total_volume_filled_baseline:{principal_token}
+*/
+#[substreams::handlers::store]
+fn store_volume_filled_baseline(
+    events: contract::Events,
+    bid_loan_details_list: teller::BidLoanDetailsList,
+    volume_filled_deltas: Deltas<DeltaBigInt>,
+    bigint_set_if_not_exists_store: StoreSetIfNotExistsBigInt,
+) {
+    let ord = 0; // FOR NOW
+
+    // end of block value of every counter first created in this block
+    let mut created_volume_filled: BTreeMap<String, BigInt> = BTreeMap::new();
+    let mut first_seen_keys: BTreeSet<String> = BTreeSet::new();
+    volume_filled_deltas.deltas.iter().for_each(|delta| {
+        if delta.operation == substreams::pb::substreams::store_delta::Operation::Create {
+            first_seen_keys.insert(delta.key.clone());
+        }
+        if first_seen_keys.contains(&delta.key) {
+            created_volume_filled.insert(delta.key.clone(), delta.new_value.clone());
+        }
+    });
+
+    if created_volume_filled.is_empty() {
+        return;
+    }
+
+    let teller_v2_address = Address::from_slice(&TELLERV2_TRACKED_CONTRACT);
+    let bid_loan_details_by_id = bid_loan_details_by_id(&bid_loan_details_list);
+
+    events.tellerv2_accepted_bids.iter().for_each(|evt| {
+        let bid_loan_details = match bid_loan_details_by_id.get(&evt.bid_id) {
+            Some(bid_loan_details) => bid_loan_details,
+            None => return,
+        };
+
+        let principal_token = address_to_string(&bid_loan_details.principal_token_address);
+        let lender_address = Address::from_slice(&evt.lender);
+
+        let lender_key = format!(
+            "lender_volume_filled:{}:{}",
+            principal_token,
+            address_to_string(&lender_address)
+        );
+        let total_key = format!("total_volume_filled:{}", principal_token);
+
+        let indexed_lender_volume_filled = created_volume_filled.get(&lender_key);
+        let indexed_total_volume_filled = created_volume_filled.get(&total_key);
+        if indexed_lender_volume_filled.is_none() && indexed_total_volume_filled.is_none() {
+            return;
+        }
+
+        let contract_volume_filled = rpc::tellerv2::fetch_volume_filled_from_rpc(
+            &teller_v2_address,
+            &bid_loan_details.principal_token_address,
+            &lender_address,
+        );
+
+        if let (Some(indexed), Some(contract)) =
+            (indexed_lender_volume_filled, &contract_volume_filled.lender_volume_filled)
+        {
+            bigint_set_if_not_exists_store.set_if_not_exists(
+                ord,
+                format!(
+                    "lender_volume_filled_baseline:{}:{}",
+                    principal_token,
+                    address_to_string(&lender_address)
+                ),
+                &(contract.clone() - indexed.clone()),
+            );
+        }
+        if let (Some(indexed), Some(contract)) =
+            (indexed_total_volume_filled, &contract_volume_filled.total_volume_filled)
+        {
+            bigint_set_if_not_exists_store.set_if_not_exists(
+                ord,
+                format!("total_volume_filled_baseline:{}", principal_token),
+                &(contract.clone() - indexed.clone()),
+            );
+        }
+    });
+}
+
+fn graph_tellerv2_out(
+    events: &contract::Events,
+
+    bid_loan_details_by_id: &BTreeMap<String, rpc::tellerv2::BidLoanDetailsData>,
+
+    token_address_delta_store: &Deltas<DeltaBigInt>,
+
+    token_prices: &StoreGetFloat64,
+
+    token_decimals: &StoreGetBigInt,
+
+    pause_window_store: &StoreGetString,
+
+    tables: &mut EntityChangesTables,
+) {
+    events.tellerv2_submitted_bids.iter().for_each(|evt| {
+        let (metadata_uri_text, metadata_scheme) =
+            decode_metadata_uri(&evt.metadata_uri_text, &evt.metadata_uri);
+
+        let submitted_bid_row = tables
+            .create_row(
+                "tellerv2_submitted_bid",
+                format!("{}-{}", evt.evt_tx_hash, evt.evt_index),
+            )
+            .set("evt_tx_hash", &evt.evt_tx_hash)
+            .set("evt_index", evt.evt_index)
+            .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
+            .set("evt_block_number", evt.evt_block_number)
+            .set("bid_id", BigDecimal::from_str(&evt.bid_id).unwrap())
+            .set("borrower", Hex(&evt.borrower).to_string())
+            .set("metadata_uri", Hex(&evt.metadata_uri).to_string())
+            .set("metadata_scheme", metadata_scheme.to_string())
+            .set("receiver", Hex(&evt.receiver).to_string());
+
+        if let Some(metadata_uri_text) = metadata_uri_text {
+            submitted_bid_row.set("metadata_uri_text", metadata_uri_text);
+        }
+
+        // submitBid is whenNotPaused so this should never happen
+        let open_pause_window = open_pause_window_at(pause_window_store, evt.evt_index);
+
+        submitted_bid_row.set("inside_pause_window", open_pause_window.is_some());
+
+        if let Some((_, pause_window_id)) = open_pause_window {
+            submitted_bid_row.set("pause_window", pause_window_id);
+        }
+
+        
+             
+            
+            
+        let bid_id = BigInt::from_str(&evt.bid_id).unwrap();
+        let teller_v2_address = Address::from_slice(&TELLERV2_TRACKED_CONTRACT);
+    
+        
+        let bid_row = tables
+                .create_row("tellerv2_bid", bid_id.to_string())
+                .set("bid_id", &bid_id)
+                .set("status", "submitted".to_string())
+                
+                .set(
+                    "borrower",
+                    Hex(  &evt.borrower ) .to_string(),
+                )
+                .set("receiver", Hex(&evt.receiver).to_string())
+                .set("collateral_count", evt.collateral.len() as u64)
+                
+                ;
+
+        if let Some(apr) = evt.apr {
+            bid_row.set("apr_bps", apr);
+        }
+
+        if let Some(duration) = evt.duration {
+            bid_row.set("duration_seconds", duration);
+        }
+
+        evt.collateral.iter().enumerate().for_each(|(collateral_index, collateral)| {
+            tables
+                .create_row(
+                    "tellerv2_bid_collateral",
+                    format!("{}-{}", bid_id, collateral_index),
+                )
+                .set("bid_id", &bid_id)
+                .set("collateral_type", collateral.collateral_type as u64)
+                .set("collateral_address", Hex(&collateral.collateral_address).to_string())
+                .set("amount", BigDecimal::from_str(&collateral.amount).unwrap())
+                .set("token_id", BigDecimal::from_str(&collateral.token_id).unwrap());
+        });
+
+        // the expiration window is relative to the submission time; a zero window never expires
+        if let Some(bid_expiration_window) =
+            rpc::tellerv2::fetch_bid_expiration_time_from_rpc(&teller_v2_address, &bid_id)
+                .filter(|window| !window.is_zero())
+        {
+            let submitted_at = evt.evt_block_time.as_ref().unwrap();
+
+            tables
+                .update_row("tellerv2_bid", bid_id.to_string())
+                .set(
+                    "expiration_time",
+                    prost_types::Timestamp {
+                        seconds: submitted_at.seconds + bid_expiration_window.to_u64() as i64,
+                        nanos: 0,
+                    },
+                );
+        }
+      if let Some(submitted_bid_data) = bid_loan_details_by_id.get(&evt.bid_id) {
+          
+          let bid_id = bid_id.clone();
+
+            let weth_address =
+                H160::from_str("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2").unwrap();
+            let usdc_address =
+                H160::from_str("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").unwrap();
+
+            let principal_token_address = submitted_bid_data.principal_token_address.clone();
+            let principal_amount = submitted_bid_data.principal_amount.clone();
+
+            let principal_amount_usdc = calculate_principal_amount_usdc(
+                principal_amount,
+                principal_token_address,
+                weth_address,
+                usdc_address,
+                &token_prices,
+                &token_decimals,
+            );
+            
+            let principal_amount_usdc_big_decimal = f64_to_bigdecimal(principal_amount_usdc);
+            
+            
+            
+                 tables
+                .update_row("tellerv2_bid", bid_id.to_string())
+               
+                .set("status", "submitted".to_string())
+                 
+                // .set("receiver", Hex(&evt.receiver).to_string())
+                .set(
+                    "principal_token_address",
+                    Hex(&submitted_bid_data.principal_token_address).to_string(),
+                )
+                .set("principal_amount", &submitted_bid_data.principal_amount)
+                .set("principal_amount_usdc", &principal_amount_usdc_big_decimal);
+            
+         }
+            
+        // }.unwrap()
+    });
+
+    // Loop over all the abis events to create table changes
+    events.tellerv2_accepted_bids.iter().for_each(|evt| {
+        
+        let event_block_time =  evt.evt_block_time.as_ref().unwrap().clone();
+        
+        let accepted_bid_row = tables
+            .create_row(
+                "tellerv2_accepted_bid",
+                format!("{}-{}", evt.evt_tx_hash, evt.evt_index),
+            )
+            .set("evt_tx_hash", &evt.evt_tx_hash)
+            .set("evt_index", evt.evt_index)
+            .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
+            .set("evt_block_number", evt.evt_block_number)
+            .set("bid_id", BigDecimal::from_str(&evt.bid_id).unwrap())
+            .set("lender", Hex(&evt.lender).to_string());
+
+        // lenderAcceptBid is whenNotPaused so this should never happen
+        let open_pause_window = open_pause_window_at(pause_window_store, evt.evt_index);
+
+        accepted_bid_row.set("inside_pause_window", open_pause_window.is_some());
+
+        if let Some((_, pause_window_id)) = open_pause_window {
+            accepted_bid_row.set("pause_window", pause_window_id);
+        }
+
+        let bid_id = BigInt::from_str(&evt.bid_id).unwrap();
+
+        let submitted_bid_data_option = bid_loan_details_by_id.get(&evt.bid_id);
+
+        /*
+
+
+            The block stream encountered a substreams fatal error and will not retry: rpc error: code = InvalidArgument desc = step new irr: handler step new: execute modules: applying executor results "graph_out" on block 15096143: execute: maps wasm call: block 15096143: module "graph_out": general wasm execution panicked: wasm execution failed deterministically: panic in the wasm: "called `Option::unwrap()` on a `None` value" at src/lib.rs:750:15
+
+        */
+
+        //  if let Some(submitted_bid_data) = submitted_bid_data {
+        if let Some(submitted_bid_data) = submitted_bid_data_option {
+            let bid_id = bid_id.clone();
+
+            let weth_address =
+                H160::from_str("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2").unwrap();
+            let usdc_address =
+                H160::from_str("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").unwrap();
+
+            let principal_token_address = submitted_bid_data.principal_token_address.clone();
+            let principal_amount = submitted_bid_data.principal_amount.clone();
+
+            let principal_amount_usdc = calculate_principal_amount_usdc(
+                principal_amount,
+                principal_token_address,
+                weth_address,
+                usdc_address,
+                &token_prices,
+                &token_decimals,
+            );
+            
+            
+
+            let principal_amount_usdc_big_decimal = f64_to_bigdecimal(principal_amount_usdc);
+
+            tables
+                .update_row("tellerv2_bid", bid_id.to_string())
+               
+                .set("status", "accepted".to_string())
+                .set("accepted_at", event_block_time )
+                .set(
+                    "borrower",
+                    Hex(&submitted_bid_data.borrower_address).to_string(),
+                )
+                .set(
+                    "lender",
+                    Hex(&submitted_bid_data.lender_address).to_string(),
+                )
+                // .set("receiver", Hex(&evt.receiver).to_string())
+                .set(
+                    "principal_token_address",
+                    Hex(&submitted_bid_data.principal_token_address).to_string(),
+                )
+                .set("principal_amount", &submitted_bid_data.principal_amount)
+                .set("principal_amount_usdc", &principal_amount_usdc_big_decimal);
+        }
+    });
+
+    events.tellerv2_cancelled_bids.iter().for_each(|evt| {
+        tables
+            .create_row(
+                "tellerv2_cancelled_bid",
+                format!("{}-{}", evt.evt_tx_hash, evt.evt_index),
+            )
+            .set("evt_tx_hash", &evt.evt_tx_hash)
+            .set("evt_index", evt.evt_index)
+            .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
+            .set("evt_block_number", evt.evt_block_number)
+            .set("bid_id", BigDecimal::from_str(&evt.bid_id).unwrap());
+            
+            
+                let bid_id = BigInt::from_str(&evt.bid_id).unwrap();
+                    
+                
+                  tables
+                .update_row("tellerv2_bid", bid_id.to_string())
+                  
+                .set("status", "cancelled".to_string())
+               ;
+             
+             
+                
+    });
+    events.tellerv2_fee_paids.iter().for_each(|evt| {
+        tables
+            .create_row(
+                "tellerv2_fee_paid",
+                format!("{}-{}", evt.evt_tx_hash, evt.evt_index),
+            )
+            .set("evt_tx_hash", &evt.evt_tx_hash)
+            .set("evt_index", evt.evt_index)
+            .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
+            .set("evt_block_number", evt.evt_block_number)
+            .set("amount", BigDecimal::from_str(&evt.amount).unwrap())
+            .set("bid_id", BigDecimal::from_str(&evt.bid_id).unwrap())
+            .set("fee_type", &evt.fee_type);
+    });
+    events.tellerv2_initializeds.iter().for_each(|evt| {
+        tables
+            .create_row(
+                "tellerv2_initialized",
+                format!("{}-{}", evt.evt_tx_hash, evt.evt_index),
+            )
+            .set("evt_tx_hash", &evt.evt_tx_hash)
+            .set("evt_index", evt.evt_index)
+            .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
+            .set("evt_block_number", evt.evt_block_number)
+            .set("version", evt.version);
+    });
+    events.tellerv2_loan_liquidateds.iter().for_each(|evt| {
+        tables
+            .create_row(
+                "tellerv2_loan_liquidated",
+                format!("{}-{}", evt.evt_tx_hash, evt.evt_index),
+            )
+            .set("evt_tx_hash", &evt.evt_tx_hash)
+            .set("evt_index", evt.evt_index)
+            .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
+            .set("evt_block_number", evt.evt_block_number)
+            .set("bid_id", BigDecimal::from_str(&evt.bid_id).unwrap())
+            .set("liquidator", Hex(&evt.liquidator).to_string());
+            
+            
+                    let bid_id = BigInt::from_str(&evt.bid_id).unwrap();
+            
+                                                            
+                  
+                
+                tables
+                .update_row("tellerv2_bid", bid_id.to_string())
+                  
+                .set("status", "liquidated".to_string()) ;
+                
+            
+    });
+    events.tellerv2_loan_repaids.iter().for_each(|evt| {
+        tables
+            .create_row(
+                "tellerv2_loan_repaid",
+                format!("{}-{}", evt.evt_tx_hash, evt.evt_index),
+            )
+            .set("evt_tx_hash", &evt.evt_tx_hash)
+            .set("evt_index", evt.evt_index)
+            .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
+            .set("evt_block_number", evt.evt_block_number)
+            .set("bid_id", BigDecimal::from_str(&evt.bid_id).unwrap());
+            
+            
+                let bid_id = BigInt::from_str(&evt.bid_id).unwrap();
+                 
+                // liquidateLoanFull emits LoanRepaid before LoanLiquidated, the later status wins in the same block
+                if !events
+                    .tellerv2_loan_liquidateds
+                    .iter()
+                    .any(|liquidated_evt| liquidated_evt.bid_id == evt.bid_id)
+                {
+                    tables
+                        .update_row("tellerv2_bid", bid_id.to_string())
+                        .set("status", "repaid".to_string());
+                }
+            
     });
     events.tellerv2_loan_repayments.iter().for_each(|evt| {
         tables
             .create_row(
-                "tellerv2_loan_repayment",
+                "tellerv2_loan_repayment",
+                format!("{}-{}", evt.evt_tx_hash, evt.evt_index),
+            )
+            .set("evt_tx_hash", &evt.evt_tx_hash)
+            .set("evt_index", evt.evt_index)
+            .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
+            .set("evt_block_number", evt.evt_block_number)
+            .set("bid_id", BigDecimal::from_str(&evt.bid_id).unwrap());
+    });
+    events
+        .tellerv2_market_forwarder_approveds
+        .iter()
+        .for_each(|evt| {
+            tables
+                .create_row(
+                    "tellerv2_market_forwarder_approved",
+                    format!("{}-{}", evt.evt_tx_hash, evt.evt_index),
+                )
+                .set("evt_tx_hash", &evt.evt_tx_hash)
+                .set("evt_index", evt.evt_index)
+                .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
+                .set("evt_block_number", evt.evt_block_number)
+                .set("forwarder", Hex(&evt.forwarder).to_string())
+                .set("market_id", BigDecimal::from_str(&evt.market_id).unwrap())
+                .set("sender", Hex(&evt.sender).to_string());
+        });
+    events
+        .tellerv2_market_forwarder_renounceds
+        .iter()
+        .for_each(|evt| {
+            tables
+                .create_row(
+                    "tellerv2_market_forwarder_renounced",
+                    format!("{}-{}", evt.evt_tx_hash, evt.evt_index),
+                )
+                .set("evt_tx_hash", &evt.evt_tx_hash)
+                .set("evt_index", evt.evt_index)
+                .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
+                .set("evt_block_number", evt.evt_block_number)
+                .set("forwarder", Hex(&evt.forwarder).to_string())
+                .set("market_id", BigDecimal::from_str(&evt.market_id).unwrap())
+                .set("sender", Hex(&evt.sender).to_string());
+        });
+
+    // renouncing without a prior approval is allowed by the contract, so write every required field
+    for forwarder_status_change in forwarder_status_changes(events) {
+        tables
+            .create_row(
+                "forwarder_approval",
+                format!(
+                    "{}-{}-{}",
+                    forwarder_status_change.market_id,
+                    Hex(forwarder_status_change.forwarder),
+                    Hex(forwarder_status_change.sender)
+                ),
+            )
+            .set("market_id", BigDecimal::from_str(forwarder_status_change.market_id).unwrap())
+            .set("forwarder", Hex(forwarder_status_change.forwarder).to_string())
+            .set("sender", Hex(forwarder_status_change.sender).to_string())
+            .set("is_active", forwarder_status_change.is_active)
+            .set("status_changed_at", forwarder_status_change.evt_block_time)
+            .set("status_changed_block_number", forwarder_status_change.evt_block_number);
+    }
+    events
+        .tellerv2_market_owner_cancelled_bids
+        .iter()
+        .for_each(|evt| {
+            tables
+                .create_row(
+                    "tellerv2_market_owner_cancelled_bid",
+                    format!("{}-{}", evt.evt_tx_hash, evt.evt_index),
+                )
+                .set("evt_tx_hash", &evt.evt_tx_hash)
+                .set("evt_index", evt.evt_index)
+                .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
+                .set("evt_block_number", evt.evt_block_number)
+                .set("bid_id", BigDecimal::from_str(&evt.bid_id).unwrap());
+        });
+    events
+        .tellerv2_ownership_transferreds
+        .iter()
+        .for_each(|evt| {
+            tables
+                .create_row(
+                    "tellerv2_ownership_transferred",
+                    format!("{}-{}", evt.evt_tx_hash, evt.evt_index),
+                )
+                .set("evt_tx_hash", &evt.evt_tx_hash)
+                .set("evt_index", evt.evt_index)
+                .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
+                .set("evt_block_number", evt.evt_block_number)
+                .set("new_owner", Hex(&evt.new_owner).to_string())
+                .set("previous_owner", Hex(&evt.previous_owner).to_string());
+        });
+    events.tellerv2_pauseds.iter().for_each(|evt| {
+        tables
+            .create_row(
+                "tellerv2_paused",
                 format!("{}-{}", evt.evt_tx_hash, evt.evt_index),
             )
             .set("evt_tx_hash", &evt.evt_tx_hash)
             .set("evt_index", evt.evt_index)
             .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
             .set("evt_block_number", evt.evt_block_number)
-            .set("bid_id", BigDecimal::from_str(&evt.bid_id).unwrap());
+            .set("account", Hex(&evt.account).to_string());
+
+        tables
+            .create_row(
+                "pause_window",
+                format!("{}-{}", evt.evt_tx_hash, evt.evt_index),
+            )
+            .set("started_at", evt.evt_block_time.as_ref().unwrap())
+            .set("started_block_number", evt.evt_block_number)
+            .set("paused_by", Hex(&evt.account).to_string())
+            .set("is_open", true);
+    });
+    events.tellerv2_protocol_fee_sets.iter().for_each(|evt| {
+        tables
+            .create_row(
+                "tellerv2_protocol_fee_set",
+                format!("{}-{}", evt.evt_tx_hash, evt.evt_index),
+            )
+            .set("evt_tx_hash", &evt.evt_tx_hash)
+            .set("evt_index", evt.evt_index)
+            .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
+            .set("evt_block_number", evt.evt_block_number)
+            .set("new_fee", evt.new_fee)
+            .set("old_fee", evt.old_fee);
     });
+
     events
-        .tellerv2_market_forwarder_approveds
+        .tellerv2_trusted_market_forwarder_sets
         .iter()
         .for_each(|evt| {
             tables
                 .create_row(
-                    "tellerv2_market_forwarder_approved",
+                    "tellerv2_trusted_market_forwarder_set",
                     format!("{}-{}", evt.evt_tx_hash, evt.evt_index),
                 )
-                .set("evt_tx_hash", &evt.evt_tx_hash)
-                .set("evt_index", evt.evt_index)
-                .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
-                .set("evt_block_number", evt.evt_block_number)
-                .set("forwarder", Hex(&evt.forwarder).to_string())
-                .set("market_id", BigDecimal::from_str(&evt.market_id).unwrap())
-                .set("sender", Hex(&evt.sender).to_string());
-        });
-    events
-        .tellerv2_market_forwarder_renounceds
-        .iter()
-        .for_each(|evt| {
+                .set("evt_tx_hash", &evt.evt_tx_hash)
+                .set("evt_index", evt.evt_index)
+                .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
+                .set("evt_block_number", evt.evt_block_number)
+                .set("forwarder", Hex(&evt.forwarder).to_string())
+                .set("market_id", BigDecimal::from_str(&evt.market_id).unwrap())
+                .set("sender", Hex(&evt.sender).to_string());
+
+            tables
+                .create_row("market_forwarder", evt.market_id.clone())
+                .set("market_id", BigDecimal::from_str(&evt.market_id).unwrap())
+                .set("trusted_forwarder", Hex(&evt.forwarder).to_string())
+                .set("set_by", Hex(&evt.sender).to_string())
+                .set("updated_at", evt.evt_block_time.as_ref().unwrap())
+                .set("updated_block_number", evt.evt_block_number);
+        });
+    events.tellerv2_unpauseds.iter().for_each(|evt| {
+        tables
+            .create_row(
+                "tellerv2_unpaused",
+                format!("{}-{}", evt.evt_tx_hash, evt.evt_index),
+            )
+            .set("evt_tx_hash", &evt.evt_tx_hash)
+            .set("evt_index", evt.evt_index)
+            .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
+            .set("evt_block_number", evt.evt_block_number)
+            .set("account", Hex(&evt.account).to_string());
+
+        if let Some((started_at, pause_window_id)) =
+            open_pause_window_at(pause_window_store, evt.evt_index)
+        {
+            let ended_at = evt.evt_block_time.as_ref().unwrap();
+
+            tables
+                .update_row("pause_window", pause_window_id)
+                .set("ended_at", ended_at)
+                .set("ended_block_number", evt.evt_block_number)
+                .set("unpaused_by", Hex(&evt.account).to_string())
+                .set("duration_seconds", (ended_at.seconds - started_at) as u64)
+                .set("is_open", false);
+        }
+    });
+
+    for token_address_delta in token_address_delta_store.iter() {
+        let ord = 0; // FOR NOW
+
+        let token_address = &token_address_delta.key;
+
+        let token_price_option = token_prices.get_at(ord, token_address);
+
+        let WETH_ADDRESS = "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2";
+
+        if let Some(token_price) = token_price_option {
+            tables
+                .create_row("token_price", token_address.clone())
+                .set("base_token_address", token_address)
+                .set("reference_token_address", WETH_ADDRESS)
+                .set("price_ratio", token_price.to_string());
+        }
+    }
+}
+
+fn graph_principal_day_data_out(
+    clock: &Clock,
+
+    token_address_delta_store: &Deltas<DeltaBigInt>,
+
+    token_principal: &StoreGetBigInt,
+
+    protocol_principal_usdc: &StoreGetFloat64,
+
+    token_prices: &StoreGetFloat64,
+
+    token_decimals: &StoreGetBigInt,
+
+    tables: &mut EntityChangesTables,
+) {
+    let ord = 0; // FOR NOW
+
+    let block_time = clock.timestamp.as_ref().unwrap().clone();
+    let day_index = block_time.seconds / 86400;
+
+    let weth_address = H160::from_str("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2").unwrap();
+    let usdc_address = H160::from_str("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").unwrap();
+
+    let mut principal_activity_occured = false;
+
+    for token_address_delta in token_address_delta_store.iter() {
+        let token_address = &token_address_delta.key;
+
+        // usdc is always pushed into the interaction deltas for pricing, skip it unless it has been lent
+        let outstanding_principal = match token_principal
+            .get_at(ord, format!("token_principal:{}:outstanding", token_address))
+        {
+            Some(outstanding_principal) => outstanding_principal,
+            None => continue,
+        };
+
+        let get_total = |flow_type: &str| {
+            token_principal
+                .get_at(ord, format!("token_principal:{}:{}", token_address, flow_type))
+                .unwrap_or(BigInt::zero())
+        };
+
+        let get_daily = |flow_type: &str| {
+            token_principal
+                .get_at(
+                    ord,
+                    format!(
+                        "token_principal_daily:{}:{}:{}",
+                        token_address, day_index, flow_type
+                    ),
+                )
+                .unwrap_or(BigInt::zero())
+        };
+
+        let to_usdc = |amount: &BigInt| {
+            f64_to_bigdecimal(calculate_principal_amount_usdc(
+                amount.clone(),
+                H160::from_str(token_address.as_str()).unwrap(),
+                weth_address,
+                usdc_address,
+                token_prices,
+                token_decimals,
+            ))
+        };
+
+        let originated_principal = get_daily("originated");
+        let repaid_principal = get_daily("repaid");
+        let liquidated_principal = get_daily("liquidated");
+
+        tables
+            .create_row("token_day_data", format!("{}-{}", token_address, day_index))
+            .set("token_address", token_address)
+            .set("day_index", day_index as u64)
+            .set("block_number", clock.number)
+            .set("block_time", &block_time)
+            .set("outstanding_principal", &outstanding_principal)
+            .set("originated_principal", &originated_principal)
+            .set("repaid_principal", &repaid_principal)
+            .set("liquidated_principal", &liquidated_principal)
+            .set("total_originated_principal", &get_total("originated"))
+            .set("total_repaid_principal", &get_total("repaid"))
+            .set("total_liquidated_principal", &get_total("liquidated"))
+            .set("outstanding_principal_usdc", &to_usdc(&outstanding_principal))
+            .set("originated_principal_usdc", &to_usdc(&originated_principal))
+            .set("repaid_principal_usdc", &to_usdc(&repaid_principal))
+            .set("liquidated_principal_usdc", &to_usdc(&liquidated_principal));
+
+        principal_activity_occured = true;
+    }
+
+    if principal_activity_occured {
+        let get_usdc = |key: String| {
+            f64_to_bigdecimal(protocol_principal_usdc.get_at(ord, key).unwrap_or(0.0))
+        };
+
+        tables
+            .create_row("protocol_day_data", day_index.to_string())
+            .set("day_index", day_index as u64)
+            .set("block_number", clock.number)
+            .set("block_time", &block_time)
+            .set(
+                "outstanding_principal_usdc",
+                &get_usdc("protocol_principal_usdc:outstanding".to_string()),
+            )
+            .set(
+                "originated_principal_usdc",
+                &get_usdc(format!("protocol_principal_usdc_daily:{}:originated", day_index)),
+            )
+            .set(
+                "repaid_principal_usdc",
+                &get_usdc(format!("protocol_principal_usdc_daily:{}:repaid", day_index)),
+            )
+            .set(
+                "liquidated_principal_usdc",
+                &get_usdc(format!("protocol_principal_usdc_daily:{}:liquidated", day_index)),
+            )
+            .set(
+                "total_originated_principal_usdc",
+                &get_usdc("protocol_principal_usdc:originated".to_string()),
+            )
+            .set(
+                "total_repaid_principal_usdc",
+                &get_usdc("protocol_principal_usdc:repaid".to_string()),
+            )
+            .set(
+                "total_liquidated_principal_usdc",
+                &get_usdc("protocol_principal_usdc:liquidated".to_string()),
+            );
+    }
+}
+
+fn db_principal_day_data_out(
+    clock: &Clock,
+
+    token_address_delta_store: &Deltas<DeltaBigInt>,
+
+    token_principal: &StoreGetBigInt,
+
+    protocol_principal_usdc: &StoreGetFloat64,
+
+    token_prices: &StoreGetFloat64,
+
+    token_decimals: &StoreGetBigInt,
+
+    tables: &mut DatabaseChangeTables,
+) {
+    let ord = 0; // FOR NOW
+
+    let block_time = clock.timestamp.as_ref().unwrap().clone();
+    let day_index = block_time.seconds / 86400;
+
+    let weth_address = H160::from_str("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2").unwrap();
+    let usdc_address = H160::from_str("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").unwrap();
+
+    let mut principal_activity_occured = false;
+
+    for token_address_delta in token_address_delta_store.iter() {
+        let token_address = &token_address_delta.key;
+
+        // usdc is always pushed into the interaction deltas for pricing, skip it unless it has been lent
+        let outstanding_principal = match token_principal
+            .get_at(ord, format!("token_principal:{}:outstanding", token_address))
+        {
+            Some(outstanding_principal) => outstanding_principal,
+            None => continue,
+        };
+
+        let get_total = |flow_type: &str| {
+            token_principal
+                .get_at(ord, format!("token_principal:{}:{}", token_address, flow_type))
+                .unwrap_or(BigInt::zero())
+        };
+
+        let get_daily = |flow_type: &str| {
+            token_principal
+                .get_at(
+                    ord,
+                    format!(
+                        "token_principal_daily:{}:{}:{}",
+                        token_address, day_index, flow_type
+                    ),
+                )
+                .unwrap_or(BigInt::zero())
+        };
+
+        let to_usdc = |amount: &BigInt| {
+            f64_to_bigdecimal(calculate_principal_amount_usdc(
+                amount.clone(),
+                H160::from_str(token_address.as_str()).unwrap(),
+                weth_address,
+                usdc_address,
+                token_prices,
+                token_decimals,
+            ))
+        };
+
+        let originated_principal = get_daily("originated");
+        let repaid_principal = get_daily("repaid");
+        let liquidated_principal = get_daily("liquidated");
+
+        tables
+            .upsert_row(
+                "token_day_data",
+                [
+                    ("token_address", token_address.clone()),
+                    ("day_index", day_index.to_string()),
+                ],
+            )
+            .set("block_number", clock.number)
+            .set("block_time", &block_time)
+            .set("outstanding_principal", &outstanding_principal)
+            .set("originated_principal", &originated_principal)
+            .set("repaid_principal", &repaid_principal)
+            .set("liquidated_principal", &liquidated_principal)
+            .set("total_originated_principal", &get_total("originated"))
+            .set("total_repaid_principal", &get_total("repaid"))
+            .set("total_liquidated_principal", &get_total("liquidated"))
+            .set("outstanding_principal_usdc", &to_usdc(&outstanding_principal))
+            .set("originated_principal_usdc", &to_usdc(&originated_principal))
+            .set("repaid_principal_usdc", &to_usdc(&repaid_principal))
+            .set("liquidated_principal_usdc", &to_usdc(&liquidated_principal));
+
+        principal_activity_occured = true;
+    }
+
+    if principal_activity_occured {
+        let get_usdc = |key: String| {
+            f64_to_bigdecimal(protocol_principal_usdc.get_at(ord, key).unwrap_or(0.0))
+        };
+
+        tables
+            .upsert_row("protocol_day_data", day_index.to_string())
+            .set("block_number", clock.number)
+            .set("block_time", &block_time)
+            .set(
+                "outstanding_principal_usdc",
+                &get_usdc("protocol_principal_usdc:outstanding".to_string()),
+            )
+            .set(
+                "originated_principal_usdc",
+                &get_usdc(format!("protocol_principal_usdc_daily:{}:originated", day_index)),
+            )
+            .set(
+                "repaid_principal_usdc",
+                &get_usdc(format!("protocol_principal_usdc_daily:{}:repaid", day_index)),
+            )
+            .set(
+                "liquidated_principal_usdc",
+                &get_usdc(format!("protocol_principal_usdc_daily:{}:liquidated", day_index)),
+            )
+            .set(
+                "total_originated_principal_usdc",
+                &get_usdc("protocol_principal_usdc:originated".to_string()),
+            )
+            .set(
+                "total_repaid_principal_usdc",
+                &get_usdc("protocol_principal_usdc:repaid".to_string()),
+            )
+            .set(
+                "total_liquidated_principal_usdc",
+                &get_usdc("protocol_principal_usdc:liquidated".to_string()),
+            );
+    }
+}
+
+/*
+  activity_hour_window / activity_day_window rows.
+
+  A window is opened by the first block whose clock falls inside it and closed by the first block past it,
+  so quiet windows still show up with zero activity.
+*/
+fn graph_activity_windows_out(
+    clock: &Clock,
+
+    events: &contract::Events,
+
+    hour_index_deltas: &Deltas<DeltaBigInt>,
+
+    day_index_deltas: &Deltas<DeltaBigInt>,
+
+    activity_window_counts: &StoreGetBigInt,
+
+    activity_window_usdc: &StoreGetFloat64,
+
+    tables: &mut EntityChangesTables,
+) {
+    let ord = 0; // FOR NOW
+
+    let block_time = clock.timestamp.as_ref().unwrap().clone();
+
+    let activity_occured = !events.tellerv2_submitted_bids.is_empty()
+        || !events.tellerv2_accepted_bids.is_empty()
+        || !events.tellerv2_loan_repayments.is_empty()
+        || !events.tellerv2_loan_repaids.is_empty()
+        || !events.tellerv2_loan_liquidateds.is_empty()
+        || !events.tellerv2_fee_paids.is_empty();
+
+    for (period, period_seconds) in ACTIVITY_WINDOW_PERIODS {
+        let window_index_deltas = match period {
+            "hour" => hour_index_deltas,
+            _ => day_index_deltas,
+        };
+
+        let table_name = format!("activity_{}_window", period);
+
+        let window_index = block_time.seconds / period_seconds;
+
+        let mut window_opened = false;
+
+        for window_index_delta in window_index_deltas.iter() {
+            let previous_window_index = &window_index_delta.old_value;
+
+            if previous_window_index == &window_index_delta.new_value {
+                continue;
+            }
+
+            if !previous_window_index.is_zero() {
+                tables
+                    .update_row(&table_name, previous_window_index.to_string())
+                    .set("closed", true)
+                    .set("close_block_number", clock.number)
+                    .set("close_block_time", &block_time);
+            }
+
+            window_opened = true;
+        }
+
+        if !window_opened && !activity_occured {
+            continue;
+        }
+
+        let get_count = |field: &str| {
+            activity_window_counts
+                .get_at(
+                    ord,
+                    format!("activity_window:{}:{}:{}", period, window_index, field),
+                )
+                .unwrap_or(BigInt::zero())
+        };
+
+        let get_usdc = |flow: &str| {
+            f64_to_bigdecimal(
+                activity_window_usdc
+                    .get_at(
+                        ord,
+                        format!("activity_window_usdc:{}:{}:{}", period, window_index, flow),
+                    )
+                    .unwrap_or(0.0),
+            )
+        };
+
+        let window_row = match window_opened {
+            true => tables
+                .create_row(&table_name, window_index.to_string())
+                .set("window_index", window_index as u64)
+                .set("period_start_unix", (window_index * period_seconds) as u64)
+                .set("period_end_unix", ((window_index + 1) * period_seconds) as u64)
+                .set("open_block_number", clock.number)
+                .set("open_block_time", &block_time)
+                .set("closed", false),
+            false => tables.update_row(&table_name, window_index.to_string()),
+        };
+
+        window_row
+            .set("submitted_bid_count", &get_count("submitted_bids"))
+            .set("accepted_bid_count", &get_count("accepted_bids"))
+            .set("repayment_count", &get_count("repayments"))
+            .set("liquidation_count", &get_count("liquidations"))
+            .set("fee_payment_count", &get_count("fee_payments"))
+            .set("originated_principal_usdc", &get_usdc("originated"))
+            .set("repaid_principal_usdc", &get_usdc("repaid_principal"))
+            .set("repaid_interest_usdc", &get_usdc("repaid_interest"))
+            .set("liquidated_principal_usdc", &get_usdc("liquidated"))
+            .set("fees_usdc", &get_usdc("fees"));
+    }
+}
+
+fn db_activity_windows_out(
+    clock: &Clock,
+
+    events: &contract::Events,
+
+    hour_index_deltas: &Deltas<DeltaBigInt>,
+
+    day_index_deltas: &Deltas<DeltaBigInt>,
+
+    activity_window_counts: &StoreGetBigInt,
+
+    activity_window_usdc: &StoreGetFloat64,
+
+    tables: &mut DatabaseChangeTables,
+) {
+    let ord = 0; // FOR NOW
+
+    let block_time = clock.timestamp.as_ref().unwrap().clone();
+
+    let activity_occured = !events.tellerv2_submitted_bids.is_empty()
+        || !events.tellerv2_accepted_bids.is_empty()
+        || !events.tellerv2_loan_repayments.is_empty()
+        || !events.tellerv2_loan_repaids.is_empty()
+        || !events.tellerv2_loan_liquidateds.is_empty()
+        || !events.tellerv2_fee_paids.is_empty();
+
+    for (period, period_seconds) in ACTIVITY_WINDOW_PERIODS {
+        let window_index_deltas = match period {
+            "hour" => hour_index_deltas,
+            _ => day_index_deltas,
+        };
+
+        let table_name = format!("activity_{}_window", period);
+
+        let window_index = block_time.seconds / period_seconds;
+
+        let mut window_opened = false;
+
+        for window_index_delta in window_index_deltas.iter() {
+            let previous_window_index = &window_index_delta.old_value;
+
+            if previous_window_index == &window_index_delta.new_value {
+                continue;
+            }
+
+            if !previous_window_index.is_zero() {
+                tables
+                    .upsert_row(&table_name, previous_window_index.to_string())
+                    .set("closed", true)
+                    .set("close_block_number", clock.number)
+                    .set("close_block_time", &block_time);
+            }
+
+            window_opened = true;
+        }
+
+        if !window_opened && !activity_occured {
+            continue;
+        }
+
+        let get_count = |field: &str| {
+            activity_window_counts
+                .get_at(
+                    ord,
+                    format!("activity_window:{}:{}:{}", period, window_index, field),
+                )
+                .unwrap_or(BigInt::zero())
+        };
+
+        let get_usdc = |flow: &str| {
+            f64_to_bigdecimal(
+                activity_window_usdc
+                    .get_at(
+                        ord,
+                        format!("activity_window_usdc:{}:{}:{}", period, window_index, flow),
+                    )
+                    .unwrap_or(0.0),
+            )
+        };
+
+        let window_row = tables.upsert_row(&table_name, window_index.to_string());
+
+        if window_opened {
+            window_row
+                .set("period_start_unix", (window_index * period_seconds) as u64)
+                .set("period_end_unix", ((window_index + 1) * period_seconds) as u64)
+                .set("open_block_number", clock.number)
+                .set("open_block_time", &block_time)
+                .set("closed", false);
+        }
+
+        window_row
+            .set("submitted_bid_count", &get_count("submitted_bids"))
+            .set("accepted_bid_count", &get_count("accepted_bids"))
+            .set("repayment_count", &get_count("repayments"))
+            .set("liquidation_count", &get_count("liquidations"))
+            .set("fee_payment_count", &get_count("fee_payments"))
+            .set("originated_principal_usdc", &get_usdc("originated"))
+            .set("repaid_principal_usdc", &get_usdc("repaid_principal"))
+            .set("repaid_interest_usdc", &get_usdc("repaid_interest"))
+            .set("liquidated_principal_usdc", &get_usdc("liquidated"))
+            .set("fees_usdc", &get_usdc("fees"));
+    }
+}
+
+fn graph_interest_accrual_out(
+    day_index_deltas: &Deltas<DeltaBigInt>,
+
+    active_bid_buckets: &StoreGetBigInt,
+
+    latest_accepted_bid_id: &StoreGetBigInt,
+
+    active_bid_terms: &StoreGetBigInt,
+
+    tables: &mut EntityChangesTables,
+) {
+    for day_index_delta in day_index_deltas.iter() {
+        let previous_day_index = &day_index_delta.old_value;
+        let current_day_index = &day_index_delta.new_value;
+
+        // the very first block has nothing to accrue and every other block of a day leaves the index unchanged
+        if previous_day_index.is_zero() || previous_day_index == current_day_index {
+            continue;
+        }
+
+        let period_start = previous_day_index.to_u64() as i64 * 86400;
+        let period_end = current_day_index.to_u64() as i64 * 86400;
+
+        for bid_id in open_bid_ids(active_bid_buckets, latest_accepted_bid_id) {
+            // the terms are written at log ordinals, get_last is their end of block state
+            let get_term = |term: &str| {
+                active_bid_terms.get_last(format!("active_bid:{}:{}", bid_id, term))
+            };
+
+            let (owed_principal, apr, last_repaid_timestamp, days_in_year) = match (
+                get_term("owed_principal"),
+                get_term("apr"),
+                get_term("last_repaid_timestamp"),
+                get_term("days_in_year"),
+            ) {
+                (Some(owed_principal), Some(apr), Some(last_repaid_timestamp), Some(days_in_year)) => (
+                    owed_principal,
+                    apr,
+                    last_repaid_timestamp.to_u64() as i64,
+                    days_in_year.to_u64() as i64,
+                ),
+                _ => continue, // repaid or liquidated
+            };
+
+            let accrued_interest = calculate_accrued_interest(
+                &owed_principal,
+                &apr,
+                last_repaid_timestamp,
+                period_end,
+                days_in_year,
+            );
+
+            let period_interest = calculate_accrued_interest(
+                &owed_principal,
+                &apr,
+                last_repaid_timestamp.max(period_start),
+                period_end,
+                days_in_year,
+            );
+
             tables
                 .create_row(
-                    "tellerv2_market_forwarder_renounced",
-                    format!("{}-{}", evt.evt_tx_hash, evt.evt_index),
+                    "loan_interest_accrual",
+                    format!("{}-{}", bid_id, previous_day_index),
                 )
-                .set("evt_tx_hash", &evt.evt_tx_hash)
-                .set("evt_index", evt.evt_index)
-                .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
-                .set("evt_block_number", evt.evt_block_number)
-                .set("forwarder", Hex(&evt.forwarder).to_string())
-                .set("market_id", BigDecimal::from_str(&evt.market_id).unwrap())
-                .set("sender", Hex(&evt.sender).to_string());
-        });
-    events
-        .tellerv2_market_owner_cancelled_bids
-        .iter()
-        .for_each(|evt| {
-            tables
-                .create_row(
-                    "tellerv2_market_owner_cancelled_bid",
-                    format!("{}-{}", evt.evt_tx_hash, evt.evt_index),
+                .set("bid_id", &BigInt::from_str(&bid_id).unwrap())
+                .set("day_index", previous_day_index)
+                .set(
+                    "accrued_at",
+                    prost_types::Timestamp {
+                        seconds: period_end,
+                        nanos: 0,
+                    },
                 )
-                .set("evt_tx_hash", &evt.evt_tx_hash)
-                .set("evt_index", evt.evt_index)
-                .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
-                .set("evt_block_number", evt.evt_block_number)
-                .set("bid_id", BigDecimal::from_str(&evt.bid_id).unwrap());
-        });
-    events
-        .tellerv2_ownership_transferreds
-        .iter()
-        .for_each(|evt| {
+                .set("owed_principal", &owed_principal)
+                .set("apr_bps", &apr)
+                .set("period_interest", &period_interest)
+                .set("accrued_interest", &accrued_interest);
+
             tables
-                .create_row(
-                    "tellerv2_ownership_transferred",
-                    format!("{}-{}", evt.evt_tx_hash, evt.evt_index),
+                .update_row("tellerv2_bid", bid_id.clone())
+                .set("accrued_interest", &accrued_interest);
+        }
+    }
+}
+
+fn db_interest_accrual_out(
+    day_index_deltas: &Deltas<DeltaBigInt>,
+
+    active_bid_buckets: &StoreGetBigInt,
+
+    latest_accepted_bid_id: &StoreGetBigInt,
+
+    active_bid_terms: &StoreGetBigInt,
+
+    tables: &mut DatabaseChangeTables,
+) {
+    for day_index_delta in day_index_deltas.iter() {
+        let previous_day_index = &day_index_delta.old_value;
+        let current_day_index = &day_index_delta.new_value;
+
+        // the very first block has nothing to accrue and every other block of a day leaves the index unchanged
+        if previous_day_index.is_zero() || previous_day_index == current_day_index {
+            continue;
+        }
+
+        let period_start = previous_day_index.to_u64() as i64 * 86400;
+        let period_end = current_day_index.to_u64() as i64 * 86400;
+
+        for bid_id in open_bid_ids(active_bid_buckets, latest_accepted_bid_id) {
+            // the terms are written at log ordinals, get_last is their end of block state
+            let get_term = |term: &str| {
+                active_bid_terms.get_last(format!("active_bid:{}:{}", bid_id, term))
+            };
+
+            let (owed_principal, apr, last_repaid_timestamp, days_in_year) = match (
+                get_term("owed_principal"),
+                get_term("apr"),
+                get_term("last_repaid_timestamp"),
+                get_term("days_in_year"),
+            ) {
+                (Some(owed_principal), Some(apr), Some(last_repaid_timestamp), Some(days_in_year)) => (
+                    owed_principal,
+                    apr,
+                    last_repaid_timestamp.to_u64() as i64,
+                    days_in_year.to_u64() as i64,
+                ),
+                _ => continue, // repaid or liquidated
+            };
+
+            let accrued_interest = calculate_accrued_interest(
+                &owed_principal,
+                &apr,
+                last_repaid_timestamp,
+                period_end,
+                days_in_year,
+            );
+
+            let period_interest = calculate_accrued_interest(
+                &owed_principal,
+                &apr,
+                last_repaid_timestamp.max(period_start),
+                period_end,
+                days_in_year,
+            );
+
+            tables
+                .upsert_row(
+                    "loan_interest_accrual",
+                    [
+                        ("bid_id", bid_id.clone()),
+                        ("day_index", previous_day_index.to_string()),
+                    ],
                 )
-                .set("evt_tx_hash", &evt.evt_tx_hash)
-                .set("evt_index", evt.evt_index)
-                .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
-                .set("evt_block_number", evt.evt_block_number)
-                .set("new_owner", Hex(&evt.new_owner).to_string())
-                .set("previous_owner", Hex(&evt.previous_owner).to_string());
-        });
-    events.tellerv2_pauseds.iter().for_each(|evt| {
+                .set(
+                    "accrued_at",
+                    &prost_types::Timestamp {
+                        seconds: period_end,
+                        nanos: 0,
+                    },
+                )
+                .set("owed_principal", &owed_principal)
+                .set("apr_bps", &apr)
+                .set("period_interest", &period_interest)
+                .set("accrued_interest", &accrued_interest);
+
+            tables
+                .upsert_row("tellerv2_bid", bid_id.clone())
+                .set("accrued_interest", &accrued_interest);
+        }
+    }
+}
+
+fn graph_borrower_credit_out(
+    credit_history_deltas: &Deltas<DeltaBigInt>,
+
+    longest_delinquency_deltas: &Deltas<DeltaBigInt>,
+
+    borrowed_usdc_deltas: &Deltas<DeltaFloat64>,
+
+    tables: &mut EntityChangesTables,
+) {
+    // every count of a borrower touched in the block is in the deltas (add_to_borrower_credit) so each row is complete
+    for delta in credit_history_deltas.iter() {
+        let borrower = substreams::key::segment_at(&delta.key, 1);
+        let field = substreams::key::segment_at(&delta.key, 2);
+
+        let profile_row = tables
+            .create_row("borrower_credit_profile", borrower)
+            .set("borrower", borrower)
+            .set(field, &delta.new_value);
+
+        // first sight of the borrower, the other two stores only carry a value once it has moved
+        if field == "loans_accepted"
+            && delta.operation == substreams::pb::substreams::store_delta::Operation::Create
+        {
+            profile_row
+                .set("total_borrowed_usdc", &f64_to_bigdecimal(0.0))
+                .set("longest_delinquency_seconds", &BigInt::zero());
+        }
+    }
+
+    // a partial repayment that moves the longest delinquency shows up here without touching the counts
+    for delta in longest_delinquency_deltas.iter() {
         tables
             .create_row(
-                "tellerv2_paused",
-                format!("{}-{}", evt.evt_tx_hash, evt.evt_index),
+                "borrower_credit_profile",
+                substreams::key::segment_at(&delta.key, 1),
             )
-            .set("evt_tx_hash", &evt.evt_tx_hash)
-            .set("evt_index", evt.evt_index)
-            .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
-            .set("evt_block_number", evt.evt_block_number)
-            .set("account", Hex(&evt.account).to_string());
-    });
-    events.tellerv2_protocol_fee_sets.iter().for_each(|evt| {
+            .set("longest_delinquency_seconds", &delta.new_value);
+    }
+
+    for delta in borrowed_usdc_deltas.iter() {
         tables
             .create_row(
-                "tellerv2_protocol_fee_set",
-                format!("{}-{}", evt.evt_tx_hash, evt.evt_index),
+                "borrower_credit_profile",
+                substreams::key::segment_at(&delta.key, 1),
+            )
+            .set("total_borrowed_usdc", &f64_to_bigdecimal(delta.new_value));
+    }
+}
+
+fn db_borrower_credit_out(
+    credit_history_deltas: &Deltas<DeltaBigInt>,
+
+    longest_delinquency_deltas: &Deltas<DeltaBigInt>,
+
+    borrowed_usdc_deltas: &Deltas<DeltaFloat64>,
+
+    tables: &mut DatabaseChangeTables,
+) {
+    for delta in credit_history_deltas.iter() {
+        let borrower = substreams::key::segment_at(&delta.key, 1);
+        let field = substreams::key::segment_at(&delta.key, 2);
+
+        let profile_row = tables
+            .upsert_row("borrower_credit_profile", borrower.to_string())
+            .set(field, &delta.new_value);
+
+        // first sight of the borrower, the other two stores only carry a value once it has moved
+        if field == "loans_accepted"
+            && delta.operation == substreams::pb::substreams::store_delta::Operation::Create
+        {
+            profile_row
+                .set("total_borrowed_usdc", &f64_to_bigdecimal(0.0))
+                .set("longest_delinquency_seconds", &BigInt::zero());
+        }
+    }
+
+    // a partial repayment that moves the longest delinquency shows up here without touching the counts
+    for delta in longest_delinquency_deltas.iter() {
+        tables
+            .upsert_row(
+                "borrower_credit_profile",
+                substreams::key::segment_at(&delta.key, 1).to_string(),
+            )
+            .set("longest_delinquency_seconds", &delta.new_value);
+    }
+
+    for delta in borrowed_usdc_deltas.iter() {
+        tables
+            .upsert_row(
+                "borrower_credit_profile",
+                substreams::key::segment_at(&delta.key, 1).to_string(),
+            )
+            .set("total_borrowed_usdc", &f64_to_bigdecimal(delta.new_value));
+    }
+}
+
+fn graph_vintage_out(
+    clock: &Clock,
+
+    vintage_totals_deltas: &Deltas<DeltaBigInt>,
+
+    tables: &mut EntityChangesTables,
+) {
+    let block_time = clock.timestamp.as_ref().unwrap().clone();
+    let current_month_index = month_index_from_timestamp(block_time.seconds);
+
+    let mut vintages_touched: BTreeMap<String, Vec<(String, BigInt)>> = BTreeMap::new();
+
+    for delta in vintage_totals_deltas.iter() {
+        let vintage_key = format!(
+            "{}:{}:{}",
+            substreams::key::segment_at(&delta.key, 1),
+            substreams::key::segment_at(&delta.key, 2),
+            substreams::key::segment_at(&delta.key, 3)
+        );
+
+        vintages_touched.entry(vintage_key).or_default().push((
+            substreams::key::segment_at(&delta.key, 4).to_string(),
+            delta.new_value.clone(),
+        ));
+    }
+
+    for (vintage_key, vintage_totals) in vintages_touched {
+        let market_id = substreams::key::segment_at(&vintage_key, 0);
+        let principal_token = substreams::key::segment_at(&vintage_key, 1);
+        let vintage_month_index: i64 = substreams::key::segment_at(&vintage_key, 2).parse().unwrap();
+
+        let months_since_origination = current_month_index - vintage_month_index;
+        let vintage = month_label_from_index(vintage_month_index);
+
+        let vintage_row = tables
+            .create_row(
+                "vintage_month",
+                format!(
+                    "{}-{}-{}-{}",
+                    market_id, principal_token, vintage, months_since_origination
+                ),
+            )
+            .set("market_id", &BigInt::from_str(market_id).unwrap())
+            .set("principal_token_address", principal_token)
+            .set("vintage", &vintage)
+            .set("months_since_origination", months_since_origination as u64)
+            .set("block_number", clock.number)
+            .set("block_time", &block_time);
+
+        for (field, value) in vintage_totals {
+            vintage_row.set(&field, &value);
+        }
+    }
+}
+
+fn db_vintage_out(
+    clock: &Clock,
+
+    vintage_totals_deltas: &Deltas<DeltaBigInt>,
+
+    tables: &mut DatabaseChangeTables,
+) {
+    let block_time = clock.timestamp.as_ref().unwrap().clone();
+    let current_month_index = month_index_from_timestamp(block_time.seconds);
+
+    let mut vintages_touched: BTreeMap<String, Vec<(String, BigInt)>> = BTreeMap::new();
+
+    for delta in vintage_totals_deltas.iter() {
+        let vintage_key = format!(
+            "{}:{}:{}",
+            substreams::key::segment_at(&delta.key, 1),
+            substreams::key::segment_at(&delta.key, 2),
+            substreams::key::segment_at(&delta.key, 3)
+        );
+
+        vintages_touched.entry(vintage_key).or_default().push((
+            substreams::key::segment_at(&delta.key, 4).to_string(),
+            delta.new_value.clone(),
+        ));
+    }
+
+    for (vintage_key, vintage_totals) in vintages_touched {
+        let market_id = substreams::key::segment_at(&vintage_key, 0);
+        let principal_token = substreams::key::segment_at(&vintage_key, 1);
+        let vintage_month_index: i64 = substreams::key::segment_at(&vintage_key, 2).parse().unwrap();
+
+        let months_since_origination = current_month_index - vintage_month_index;
+        let vintage = month_label_from_index(vintage_month_index);
+
+        let vintage_row = tables
+            .upsert_row(
+                "vintage_month",
+                [
+                    ("market_id", market_id.to_string()),
+                    ("principal_token_address", principal_token.to_string()),
+                    ("vintage", vintage.clone()),
+                    ("months_since_origination", months_since_origination.to_string()),
+                ],
+            )
+            .set("block_number", clock.number)
+            .set("block_time", &block_time);
+
+        for (field, value) in vintage_totals {
+            vintage_row.set(&field, &value);
+        }
+    }
+}
+
+struct GovernanceAction<'a> {
+    evt_tx_hash: &'a String,
+
+    evt_index: u32,
+
+    evt_block_time: &'a prost_types::Timestamp,
+
+    evt_block_number: u64,
+
+    action: &'static str,
+
+    previous_value: Option<String>,
+
+    new_value: Option<String>,
+
+    account: Option<String>,
+}
+
+/*
+  Privileged actions on the TellerV2 proxy, shared by the graph and db governance_action tables.
+  Addresses are 0x prefixed, the same encoding the lender group package uses for its governance_action rows.
+*/
+fn governance_actions(events: &contract::Events) -> Vec<GovernanceAction> {
+    let mut governance_actions: Vec<GovernanceAction> = Vec::new();
+
+    let to_address = |address: &Vec<u8>| Some(format!("0x{}", Hex(address)));
+
+    events.tellerv2_ownership_transferreds.iter().for_each(|evt| {
+        governance_actions.push(GovernanceAction {
+            evt_tx_hash: &evt.evt_tx_hash,
+            evt_index: evt.evt_index,
+            evt_block_time: evt.evt_block_time.as_ref().unwrap(),
+            evt_block_number: evt.evt_block_number,
+            action: "ownership_transferred",
+            previous_value: to_address(&evt.previous_owner),
+            new_value: to_address(&evt.new_owner),
+            account: None,
+        });
+    });
+    events.tellerv2_admin_changeds.iter().for_each(|evt| {
+        governance_actions.push(GovernanceAction {
+            evt_tx_hash: &evt.evt_tx_hash,
+            evt_index: evt.evt_index,
+            evt_block_time: evt.evt_block_time.as_ref().unwrap(),
+            evt_block_number: evt.evt_block_number,
+            action: "admin_changed",
+            previous_value: to_address(&evt.previous_admin),
+            new_value: to_address(&evt.new_admin),
+            account: None,
+        });
+    });
+    events.tellerv2_upgradeds.iter().for_each(|evt| {
+        governance_actions.push(GovernanceAction {
+            evt_tx_hash: &evt.evt_tx_hash,
+            evt_index: evt.evt_index,
+            evt_block_time: evt.evt_block_time.as_ref().unwrap(),
+            evt_block_number: evt.evt_block_number,
+            action: "implementation_upgraded",
+            previous_value: None,
+            new_value: to_address(&evt.implementation),
+            account: None,
+        });
+    });
+    events.tellerv2_pauseds.iter().for_each(|evt| {
+        governance_actions.push(GovernanceAction {
+            evt_tx_hash: &evt.evt_tx_hash,
+            evt_index: evt.evt_index,
+            evt_block_time: evt.evt_block_time.as_ref().unwrap(),
+            evt_block_number: evt.evt_block_number,
+            action: "paused",
+            previous_value: None,
+            new_value: None,
+            account: to_address(&evt.account),
+        });
+    });
+    events.tellerv2_unpauseds.iter().for_each(|evt| {
+        governance_actions.push(GovernanceAction {
+            evt_tx_hash: &evt.evt_tx_hash,
+            evt_index: evt.evt_index,
+            evt_block_time: evt.evt_block_time.as_ref().unwrap(),
+            evt_block_number: evt.evt_block_number,
+            action: "unpaused",
+            previous_value: None,
+            new_value: None,
+            account: to_address(&evt.account),
+        });
+    });
+    events.tellerv2_initializeds.iter().for_each(|evt| {
+        governance_actions.push(GovernanceAction {
+            evt_tx_hash: &evt.evt_tx_hash,
+            evt_index: evt.evt_index,
+            evt_block_time: evt.evt_block_time.as_ref().unwrap(),
+            evt_block_number: evt.evt_block_number,
+            action: "initialized",
+            previous_value: None,
+            new_value: Some(evt.version.to_string()),
+            account: None,
+        });
+    });
+
+    governance_actions
+}
+
+fn graph_governance_out(events: &contract::Events, tables: &mut EntityChangesTables) {
+    for governance_action in governance_actions(events) {
+        let governance_action_row = tables
+            .create_row(
+                "governance_action",
+                format!("{}-{}", governance_action.evt_tx_hash, governance_action.evt_index),
+            )
+            .set("evt_tx_hash", governance_action.evt_tx_hash)
+            .set("evt_index", BigInt::from(governance_action.evt_index))
+            .set("evt_block_time", governance_action.evt_block_time)
+            .set("evt_block_number", BigInt::from(governance_action.evt_block_number))
+            .set("contract_type", "tellerv2")
+            .set("contract_address", format!("0x{}", Hex(&TELLERV2_TRACKED_CONTRACT)))
+            .set("action", governance_action.action);
+
+        // entity fields can't be written as null, unset values are left out
+        if let Some(previous_value) = &governance_action.previous_value {
+            governance_action_row.set("previous_value", previous_value);
+        }
+        if let Some(new_value) = &governance_action.new_value {
+            governance_action_row.set("new_value", new_value);
+        }
+        if let Some(account) = &governance_action.account {
+            governance_action_row.set("account", account);
+        }
+    }
+}
+
+fn db_volume_filled_out(
+    volume_filled_snapshot_list: &teller::VolumeFilledSnapshotList,
+
+    tables: &mut DatabaseChangeTables,
+) {
+    for snapshot in volume_filled_snapshot_list.snapshots.iter() {
+        let snapshot_row = tables
+            .upsert_row(
+                "volume_filled_snapshot",
+                [
+                    ("evt_tx_hash", snapshot.evt_tx_hash.clone()),
+                    ("evt_index", snapshot.evt_index.to_string()),
+                ],
+            )
+            .set("evt_block_time", snapshot.evt_block_time.as_ref().unwrap())
+            .set("evt_block_number", snapshot.evt_block_number)
+            .set("bid_id", BigDecimal::from_str(&snapshot.bid_id).unwrap())
+            .set("lender", Hex(&snapshot.lender).to_string())
+            .set("principal_token_address", Hex(&snapshot.principal_token_address).to_string())
+            .set("indexed_lender_volume_filled", BigDecimal::from_str(&snapshot.indexed_lender_volume_filled).unwrap())
+            .set("indexed_total_volume_filled", BigDecimal::from_str(&snapshot.indexed_total_volume_filled).unwrap())
+            .set("volume_mismatch", snapshot.volume_mismatch);
+
+        if let Some(lender_volume_filled) = &snapshot.lender_volume_filled {
+            snapshot_row.set("lender_volume_filled", BigDecimal::from_str(lender_volume_filled).unwrap());
+        }
+        if let Some(total_volume_filled) = &snapshot.total_volume_filled {
+            snapshot_row.set("total_volume_filled", BigDecimal::from_str(total_volume_filled).unwrap());
+        }
+        if let Some(legacy_lender_volume_filled) = &snapshot.legacy_lender_volume_filled {
+            snapshot_row.set("legacy_lender_volume_filled", BigDecimal::from_str(legacy_lender_volume_filled).unwrap());
+        }
+        if let Some(legacy_total_volume_filled) = &snapshot.legacy_total_volume_filled {
+            snapshot_row.set("legacy_total_volume_filled", BigDecimal::from_str(legacy_total_volume_filled).unwrap());
+        }
+    }
+}
+
+fn db_governance_out(events: &contract::Events, tables: &mut DatabaseChangeTables) {
+    for governance_action in governance_actions(events) {
+        let governance_action_row = tables
+            .upsert_row(
+                "governance_action",
+                [
+                    ("evt_tx_hash", governance_action.evt_tx_hash.clone()),
+                    ("evt_index", governance_action.evt_index.to_string()),
+                ],
+            )
+            .set("evt_block_time", governance_action.evt_block_time)
+            .set("evt_block_number", governance_action.evt_block_number)
+            .set("contract_type", "tellerv2")
+            .set("contract_address", format!("0x{}", Hex(&TELLERV2_TRACKED_CONTRACT)))
+            .set("action", governance_action.action);
+
+        if let Some(previous_value) = &governance_action.previous_value {
+            governance_action_row.set("previous_value", previous_value);
+        }
+        if let Some(new_value) = &governance_action.new_value {
+            governance_action_row.set("new_value", new_value);
+        }
+        if let Some(account) = &governance_action.account {
+            governance_action_row.set("account", account);
+        }
+    }
+}
+
+/*
+  volumeFilled read from the contract after every AcceptedBid, next to what the indexer counted so far.
+  Runs once per block so the graph and db sinks share the same calls.
+*/
+#[substreams::handlers::map]
+fn map_volume_filled_snapshots(
+    events: contract::Events,
+    bid_loan_details_list: teller::BidLoanDetailsList,
+    volume_filled_store: StoreGetBigInt,
+    volume_filled_baseline_store: StoreGetBigInt,
+) -> Result<teller::VolumeFilledSnapshotList, substreams::errors::Error> {
+    let ord = 0; // FOR NOW
+
+    let teller_v2_address = Address::from_slice(&TELLERV2_TRACKED_CONTRACT);
+    let bid_loan_details_by_id = bid_loan_details_by_id(&bid_loan_details_list);
+
+    let mut volume_filled_snapshot_list = teller::VolumeFilledSnapshotList::default();
+
+    events.tellerv2_accepted_bids.iter().for_each(|evt| {
+        let bid_loan_details = match bid_loan_details_by_id.get(&evt.bid_id) {
+            Some(bid_loan_details) => bid_loan_details,
+            None => return,
+        };
+
+        let principal_token_address = bid_loan_details.principal_token_address;
+        let lender_address = Address::from_slice(&evt.lender);
+
+        let contract_volume_filled = rpc::tellerv2::fetch_volume_filled_from_rpc(
+            &teller_v2_address,
+            &principal_token_address,
+            &lender_address,
+        );
+
+        let indexed_lender_volume_filled = volume_filled_store
+            .get_at(
+                ord,
+                format!(
+                    "lender_volume_filled:{}:{}",
+                    address_to_string(&principal_token_address),
+                    address_to_string(&lender_address)
+                ),
+            )
+            .unwrap_or(BigInt::zero());
+
+        let indexed_total_volume_filled = volume_filled_store
+            .get_at(
+                ord,
+                format!(
+                    "total_volume_filled:{}",
+                    address_to_string(&principal_token_address)
+                ),
             )
-            .set("evt_tx_hash", &evt.evt_tx_hash)
-            .set("evt_index", evt.evt_index)
-            .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
-            .set("evt_block_number", evt.evt_block_number)
-            .set("new_fee", evt.new_fee)
-            .set("old_fee", evt.old_fee);
+            .unwrap_or(BigInt::zero());
+
+        let lender_volume_filled_baseline = volume_filled_baseline_store.get_last(format!(
+            "lender_volume_filled_baseline:{}:{}",
+            address_to_string(&principal_token_address),
+            address_to_string(&lender_address)
+        ));
+        let total_volume_filled_baseline = volume_filled_baseline_store.get_last(format!(
+            "total_volume_filled_baseline:{}",
+            address_to_string(&principal_token_address)
+        ));
+
+        // only the per token counters are comparable, the legacy ones mix every lending token.
+        // the contract side is measured from the baseline, so volume from before the initial block is left out
+        let volume_mismatch = match (
+            &contract_volume_filled.lender_volume_filled,
+            &contract_volume_filled.total_volume_filled,
+            &lender_volume_filled_baseline,
+            &total_volume_filled_baseline,
+        ) {
+            (
+                Some(lender_volume_filled),
+                Some(total_volume_filled),
+                Some(lender_volume_filled_baseline),
+                Some(total_volume_filled_baseline),
+            ) => {
+                lender_volume_filled.clone() - lender_volume_filled_baseline.clone()
+                    != indexed_lender_volume_filled
+                    || total_volume_filled.clone() - total_volume_filled_baseline.clone()
+                        != indexed_total_volume_filled
+            }
+            _ => false,
+        };
+
+        volume_filled_snapshot_list.snapshots.push(teller::VolumeFilledSnapshot {
+            evt_tx_hash: evt.evt_tx_hash.clone(),
+            evt_index: evt.evt_index,
+            evt_block_time: evt.evt_block_time.clone(),
+            evt_block_number: evt.evt_block_number,
+            bid_id: evt.bid_id.clone(),
+            lender: evt.lender.clone(),
+            principal_token_address: principal_token_address.as_bytes().to_vec(),
+            lender_volume_filled: contract_volume_filled.lender_volume_filled.map(|value| value.to_string()),
+            total_volume_filled: contract_volume_filled.total_volume_filled.map(|value| value.to_string()),
+            legacy_lender_volume_filled: contract_volume_filled
+                .legacy_lender_volume_filled
+                .map(|value| value.to_string()),
+            legacy_total_volume_filled: contract_volume_filled
+                .legacy_total_volume_filled
+                .map(|value| value.to_string()),
+            indexed_lender_volume_filled: indexed_lender_volume_filled.to_string(),
+            indexed_total_volume_filled: indexed_total_volume_filled.to_string(),
+            volume_mismatch,
+        });
     });
 
-    events
-        .tellerv2_trusted_market_forwarder_sets
-        .iter()
-        .for_each(|evt| {
-            tables
-                .create_row(
-                    "tellerv2_trusted_market_forwarder_set",
-                    format!("{}-{}", evt.evt_tx_hash, evt.evt_index),
-                )
-                .set("evt_tx_hash", &evt.evt_tx_hash)
-                .set("evt_index", evt.evt_index)
-                .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
-                .set("evt_block_number", evt.evt_block_number)
-                .set("forwarder", Hex(&evt.forwarder).to_string())
-                .set("market_id", BigDecimal::from_str(&evt.market_id).unwrap())
-                .set("sender", Hex(&evt.sender).to_string());
-        });
-    events.tellerv2_unpauseds.iter().for_each(|evt| {
-        tables
+    Ok(volume_filled_snapshot_list)
+}
+
+fn graph_volume_filled_out(
+    volume_filled_snapshot_list: &teller::VolumeFilledSnapshotList,
+
+    tables: &mut EntityChangesTables,
+) {
+    for snapshot in volume_filled_snapshot_list.snapshots.iter() {
+        let snapshot_row = tables
             .create_row(
-                "tellerv2_unpaused",
-                format!("{}-{}", evt.evt_tx_hash, evt.evt_index),
+                "volume_filled_snapshot",
+                format!("{}-{}", snapshot.evt_tx_hash, snapshot.evt_index),
             )
-            .set("evt_tx_hash", &evt.evt_tx_hash)
-            .set("evt_index", evt.evt_index)
-            .set("evt_block_time", evt.evt_block_time.as_ref().unwrap())
-            .set("evt_block_number", evt.evt_block_number)
-            .set("account", Hex(&evt.account).to_string());
-    });
-
-    for token_address_delta in token_address_delta_store.iter() {
-        let ord = 0; // FOR NOW
+            .set("evt_tx_hash", &snapshot.evt_tx_hash)
+            .set("evt_block_time", snapshot.evt_block_time.as_ref().unwrap())
+            .set("evt_block_number", snapshot.evt_block_number)
+            .set("bid_id", BigInt::from_str(&snapshot.bid_id).unwrap())
+            .set("lender", Hex(&snapshot.lender).to_string())
+            .set("principal_token_address", Hex(&snapshot.principal_token_address).to_string())
+            .set("indexed_lender_volume_filled", BigInt::from_str(&snapshot.indexed_lender_volume_filled).unwrap())
+            .set("indexed_total_volume_filled", BigInt::from_str(&snapshot.indexed_total_volume_filled).unwrap())
+            .set("volume_mismatch", snapshot.volume_mismatch);
+
+        if let Some(lender_volume_filled) = &snapshot.lender_volume_filled {
+            snapshot_row.set("lender_volume_filled", BigInt::from_str(lender_volume_filled).unwrap());
+        }
+        if let Some(total_volume_filled) = &snapshot.total_volume_filled {
+            snapshot_row.set("total_volume_filled", BigInt::from_str(total_volume_filled).unwrap());
+        }
+        if let Some(legacy_lender_volume_filled) = &snapshot.legacy_lender_volume_filled {
+            snapshot_row.set("legacy_lender_volume_filled", BigInt::from_str(legacy_lender_volume_filled).unwrap());
+        }
+        if let Some(legacy_total_volume_filled) = &snapshot.legacy_total_volume_filled {
+            snapshot_row.set("legacy_total_volume_filled", BigInt::from_str(legacy_total_volume_filled).unwrap());
+        }
+    }
+}
 
-        let token_address = &token_address_delta.key;
+/*
+  Block index used by blockFilter, so map_events and the event driven stores skip blocks without TellerV2 logs.
 
-        let token_price_option = token_prices.get_at(ord, token_address);
+  evt_addr:{emitting contract}
+  evt_sig:{topic0}, so filters can also narrow down to single events
+*/
+#[substreams::handlers::map]
+fn index_events(blk: eth::Block) -> Result<Keys, substreams::errors::Error> {
+    let mut keys: BTreeSet<String> = BTreeSet::new();
 
-        let WETH_ADDRESS = "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2";
+    for log_view in blk.logs() {
+        keys.insert(format!("evt_addr:0x{}", Hex(&log_view.log.address)));
 
-        if let Some(token_price) = token_price_option {
-            tables
-                .create_row("token_price", token_address.clone())
-                .set("base_token_address", token_address)
-                .set("reference_token_address", WETH_ADDRESS)
-                .set("price_ratio", token_price.to_string());
+        if let Some(topic0) = log_view.log.topics.first() {
+            keys.insert(format!("evt_sig:0x{}", Hex(topic0)));
         }
     }
+
+    Ok(Keys {
+        keys: keys.into_iter().collect(),
+    })
 }
 
 #[substreams::handlers::map]
@@ -1229,32 +4719,442 @@ fn map_events(blk: eth::Block) -> Result<contract::Events, substreams::errors::E
 }
 
 /*
+  Typed TellerV2 activity for downstream substreams, so they do not have to redo the RPC enrichment of graph_tellerv2_out.
+
+  Repaid amounts come from the bid_repaid_totals deltas, which are per block.  When a bid is repaid several times in one block
+  the whole amount is put on its last repayment event.
+*/
+#[substreams::handlers::map]
+fn map_bids(
+    events: contract::Events,
+    bid_loan_details_list: teller::BidLoanDetailsList,
+    bid_repaid_totals_deltas: Deltas<DeltaBigInt>,
+    token_address_delta_store: Deltas<DeltaBigInt>,
+    token_prices: StoreGetFloat64,
+    token_decimals: StoreGetBigInt,
+) -> Result<teller::BidActivity, substreams::errors::Error> {
+    let ord = 0; // FOR NOW
+
+    let mut bid_activity = teller::BidActivity::default();
+
+    let bid_loan_details_by_id = bid_loan_details_by_id(&bid_loan_details_list);
+
+    let weth_address = H160::from_str("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2").unwrap();
+    let usdc_address = H160::from_str("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").unwrap();
+
+    let to_usdc = |amount: &BigInt, token_address: Address| {
+        calculate_principal_amount_usdc(
+            amount.clone(),
+            token_address,
+            weth_address,
+            usdc_address,
+            &token_prices,
+            &token_decimals,
+        )
+    };
+
+    // repaid principal and interest of this block, by bid id
+    let mut repaid_in_block: BTreeMap<String, (BigInt, BigInt)> = BTreeMap::new();
+
+    for delta in bid_repaid_totals_deltas.iter() {
+        let bid_id = substreams::key::segment_at(&delta.key, 2).to_string();
+
+        let repaid_change = delta.new_value.clone() - delta.old_value.clone();
+
+        let repaid_amounts = repaid_in_block
+            .entry(bid_id)
+            .or_insert((BigInt::zero(), BigInt::zero()));
+
+        match substreams::key::segment_at(&delta.key, 0) {
+            "bid_repaid_principal" => repaid_amounts.0 = repaid_amounts.0.clone() + repaid_change,
+            _ => repaid_amounts.1 = repaid_amounts.1.clone() + repaid_change,
+        }
+    }
+
+    // the last event of the block that touched each bid, the bid is emitted once with its end of block state
+    let mut last_bid_events: BTreeMap<String, (String, u32, Option<prost_types::Timestamp>, u64)> =
+        BTreeMap::new();
+
+    let mut touch_bid = |bid_id: &String,
+                         evt_tx_hash: &String,
+                         evt_index: u32,
+                         evt_block_time: &Option<prost_types::Timestamp>,
+                         evt_block_number: u64| {
+        let is_later = match last_bid_events.get(bid_id) {
+            Some((_, last_evt_index, _, _)) => evt_index > *last_evt_index,
+            None => true,
+        };
+
+        if is_later {
+            last_bid_events.insert(
+                bid_id.clone(),
+                (evt_tx_hash.clone(), evt_index, evt_block_time.clone(), evt_block_number),
+            );
+        }
+    };
+
+    events.tellerv2_submitted_bids.iter().for_each(|evt| {
+        touch_bid(&evt.bid_id, &evt.evt_tx_hash, evt.evt_index, &evt.evt_block_time, evt.evt_block_number);
+    });
+    events.tellerv2_accepted_bids.iter().for_each(|evt| {
+        touch_bid(&evt.bid_id, &evt.evt_tx_hash, evt.evt_index, &evt.evt_block_time, evt.evt_block_number);
+    });
+    events.tellerv2_cancelled_bids.iter().for_each(|evt| {
+        touch_bid(&evt.bid_id, &evt.evt_tx_hash, evt.evt_index, &evt.evt_block_time, evt.evt_block_number);
+    });
+    events.tellerv2_market_owner_cancelled_bids.iter().for_each(|evt| {
+        touch_bid(&evt.bid_id, &evt.evt_tx_hash, evt.evt_index, &evt.evt_block_time, evt.evt_block_number);
+    });
+    events.tellerv2_loan_repayments.iter().for_each(|evt| {
+        touch_bid(&evt.bid_id, &evt.evt_tx_hash, evt.evt_index, &evt.evt_block_time, evt.evt_block_number);
+    });
+    events.tellerv2_loan_repaids.iter().for_each(|evt| {
+        touch_bid(&evt.bid_id, &evt.evt_tx_hash, evt.evt_index, &evt.evt_block_time, evt.evt_block_number);
+    });
+    events.tellerv2_loan_liquidateds.iter().for_each(|evt| {
+        touch_bid(&evt.bid_id, &evt.evt_tx_hash, evt.evt_index, &evt.evt_block_time, evt.evt_block_number);
+    });
+
+    let mut principal_tokens: BTreeMap<String, Address> = BTreeMap::new();
+
+    for (bid_id, (evt_tx_hash, evt_index, evt_block_time, evt_block_number)) in last_bid_events.iter() {
+        let bid_loan_details = match bid_loan_details_by_id.get(bid_id) {
+            Some(bid_loan_details) => bid_loan_details,
+            None => continue,
+        };
+
+        principal_tokens.insert(bid_id.clone(), bid_loan_details.principal_token_address);
+
+        bid_activity.bids.push(teller::Bid {
+            evt_tx_hash: evt_tx_hash.clone(),
+            evt_index: *evt_index,
+            evt_block_time: evt_block_time.clone(),
+            evt_block_number: *evt_block_number,
+            bid_id: bid_id.clone(),
+            status: bid_state_name(bid_loan_details.bid_state.to_u64()).to_string(),
+            borrower: bid_loan_details.borrower_address.as_bytes().to_vec(),
+            lender: bid_loan_details.lender_address.as_bytes().to_vec(),
+            market_id: bid_loan_details.market_id.to_string(),
+            principal_token_address: bid_loan_details.principal_token_address.as_bytes().to_vec(),
+            principal_amount: bid_loan_details.principal_amount.to_string(),
+            principal_amount_usdc: to_usdc(
+                &bid_loan_details.principal_amount,
+                bid_loan_details.principal_token_address,
+            ),
+            apr_bps: bid_loan_details.apr.to_u64(),
+            loan_duration: bid_loan_details.loan_duration.to_u64(),
+            accepted_timestamp: bid_loan_details.accepted_timestamp.to_u64(),
+            last_repaid_timestamp: bid_loan_details.last_repaid_timestamp.to_u64(),
+            total_repaid_principal: bid_loan_details.total_repaid_principal.to_string(),
+            total_repaid_interest: bid_loan_details.total_repaid_interest.to_string(),
+        });
+    }
+
+    // liquidateLoanFull repays through _repayLoan, that LoanRepaid is reported as the liquidation instead
+    for evt in events.tellerv2_loan_liquidateds.iter() {
+        let principal_token_address = match principal_tokens.get(&evt.bid_id) {
+            Some(principal_token_address) => *principal_token_address,
+            None => continue,
+        };
+
+        let (principal_liquidated, interest_liquidated) = repaid_in_block
+            .remove(&evt.bid_id)
+            .unwrap_or((BigInt::zero(), BigInt::zero()));
+
+        bid_activity.liquidations.push(teller::Liquidation {
+            evt_tx_hash: evt.evt_tx_hash.clone(),
+            evt_index: evt.evt_index,
+            evt_block_time: evt.evt_block_time.clone(),
+            evt_block_number: evt.evt_block_number,
+            bid_id: evt.bid_id.clone(),
+            liquidator: evt.liquidator.clone(),
+            principal_token_address: principal_token_address.as_bytes().to_vec(),
+            principal_liquidated_usdc: to_usdc(&principal_liquidated, principal_token_address),
+            interest_liquidated_usdc: to_usdc(&interest_liquidated, principal_token_address),
+            principal_liquidated: principal_liquidated.to_string(),
+            interest_liquidated: interest_liquidated.to_string(),
+        });
+    }
+
+    // LoanRepaid carries the same fields as LoanRepayment
+    let full_repayments: Vec<contract::Tellerv2LoanRepayment> = events
+        .tellerv2_loan_repaids
+        .iter()
+        .map(|evt| contract::Tellerv2LoanRepayment {
+            evt_tx_hash: evt.evt_tx_hash.clone(),
+            evt_index: evt.evt_index,
+            evt_block_time: evt.evt_block_time.clone(),
+            evt_block_number: evt.evt_block_number,
+            bid_id: evt.bid_id.clone(),
+        })
+        .collect();
+
+    let mut repayment_events: Vec<(&contract::Tellerv2LoanRepayment, bool)> = Vec::new();
+
+    events.tellerv2_loan_repayments.iter().for_each(|evt| {
+        repayment_events.push((evt, false));
+    });
+    full_repayments.iter().for_each(|evt| {
+        repayment_events.push((evt, true));
+    });
+
+    repayment_events.sort_by_key(|(evt, _)| evt.evt_index);
+
+    for (evt, full_repayment) in repayment_events.iter().rev() {
+        let principal_token_address = match principal_tokens.get(&evt.bid_id) {
+            Some(principal_token_address) => *principal_token_address,
+            None => continue,
+        };
+
+        // liquidated bids were removed above, every other bid gets its amounts on the last event only
+        let (principal_repaid, interest_repaid) = match repaid_in_block.remove(&evt.bid_id) {
+            Some(repaid_amounts) => repaid_amounts,
+            None => match events
+                .tellerv2_loan_liquidateds
+                .iter()
+                .any(|liquidated_evt| liquidated_evt.bid_id == evt.bid_id)
+            {
+                true => continue,
+                false => (BigInt::zero(), BigInt::zero()),
+            },
+        };
+
+        bid_activity.repayments.push(teller::Repayment {
+            evt_tx_hash: evt.evt_tx_hash.clone(),
+            evt_index: evt.evt_index,
+            evt_block_time: evt.evt_block_time.clone(),
+            evt_block_number: evt.evt_block_number,
+            bid_id: evt.bid_id.clone(),
+            full_repayment: *full_repayment,
+            principal_token_address: principal_token_address.as_bytes().to_vec(),
+            principal_repaid_usdc: to_usdc(&principal_repaid, principal_token_address),
+            interest_repaid_usdc: to_usdc(&interest_repaid, principal_token_address),
+            principal_repaid: principal_repaid.to_string(),
+            interest_repaid: interest_repaid.to_string(),
+        });
+    }
+
+    bid_activity.repayments.reverse();
+
+    for evt in events.tellerv2_fee_paids.iter() {
+        // fee payments come with accept and repay, map_bid_loan_details has every bid they touch
+        let principal_token_address = match bid_loan_details_by_id.get(&evt.bid_id) {
+            Some(bid_loan_details) => bid_loan_details.principal_token_address,
+            None => continue,
+        };
+
+        let amount = BigInt::from_str(&evt.amount).unwrap();
+
+        bid_activity.fee_payments.push(teller::FeePayment {
+            evt_tx_hash: evt.evt_tx_hash.clone(),
+            evt_index: evt.evt_index,
+            evt_block_time: evt.evt_block_time.clone(),
+            evt_block_number: evt.evt_block_number,
+            bid_id: evt.bid_id.clone(),
+            fee_type: evt.fee_type.clone(),
+            principal_token_address: principal_token_address.as_bytes().to_vec(),
+            amount_usdc: to_usdc(&amount, principal_token_address),
+            amount: amount.to_string(),
+        });
+    }
+
+    for token_address_delta in token_address_delta_store.iter() {
+        let token_address = H160::from_str(&token_address_delta.key).unwrap();
+
+        let price_ratio = match token_prices.get_at(ord, &token_address_delta.key) {
+            Some(price_ratio) => price_ratio,
+            None => continue,
+        };
+
+        let decimals = token_decimals
+            .get_at(ord, &token_address_delta.key)
+            .unwrap_or(BigInt::from(18))
+            .to_u64();
+
+        let one_token = BigInt::from_str(&format!("1{}", "0".repeat(decimals as usize))).unwrap();
+
+        bid_activity.token_prices.push(teller::TokenPrice {
+            token_address: token_address.as_bytes().to_vec(),
+            reference_token_address: weth_address.as_bytes().to_vec(),
+            price_ratio,
+            decimals,
+            price_usdc: to_usdc(&one_token, token_address),
+        });
+    }
+
+    Ok(bid_activity)
+}
+
+/*
+  Postgres side of graph_out, writing the same tables.
+*/
 #[substreams::handlers::map]
-fn db_out(events: contract::Events) -> Result<DatabaseChanges, substreams::errors::Error> {
+fn db_out(
+    clock: Clock,
+    events: contract::Events,
+    bid_loan_details_list: teller::BidLoanDetailsList,
+    token_address_delta_store: Deltas<DeltaBigInt>,
+    token_prices: StoreGetFloat64,
+    token_decimals: StoreGetBigInt,
+    token_principal: StoreGetBigInt,
+    protocol_principal_usdc: StoreGetFloat64,
+    day_index_deltas: Deltas<DeltaBigInt>,
+    active_bid_buckets: StoreGetBigInt,
+    latest_accepted_bid_id: StoreGetBigInt,
+    active_bid_terms: StoreGetBigInt,
+    borrower_credit_history_deltas: Deltas<DeltaBigInt>,
+    borrower_longest_delinquency_deltas: Deltas<DeltaBigInt>,
+    borrower_borrowed_usdc_deltas: Deltas<DeltaFloat64>,
+    vintage_totals_deltas: Deltas<DeltaBigInt>,
+    pause_window_store: StoreGetString,
+    hour_index_deltas: Deltas<DeltaBigInt>,
+    activity_window_counts: StoreGetBigInt,
+    activity_window_usdc: StoreGetFloat64,
+    volume_filled_snapshot_list: teller::VolumeFilledSnapshotList,
+) -> Result<DatabaseChanges, substreams::errors::Error> {
     // Initialize Database Changes container
     let mut tables = DatabaseChangeTables::new();
-    db_tellerv2_out(&events, &mut tables);
+    db_tellerv2_out(
+        &events,
+        &bid_loan_details_by_id(&bid_loan_details_list),
+        &token_address_delta_store,
+        &token_prices,
+        &token_decimals,
+        &pause_window_store,
+        &mut tables,
+    );
+    db_principal_day_data_out(
+        &clock,
+        &token_address_delta_store,
+        &token_principal,
+        &protocol_principal_usdc,
+        &token_prices,
+        &token_decimals,
+        &mut tables,
+    );
+    db_interest_accrual_out(
+        &day_index_deltas,
+        &active_bid_buckets,
+        &latest_accepted_bid_id,
+        &active_bid_terms,
+        &mut tables,
+    );
+    db_borrower_credit_out(
+        &borrower_credit_history_deltas,
+        &borrower_longest_delinquency_deltas,
+        &borrower_borrowed_usdc_deltas,
+        &mut tables,
+    );
+    db_vintage_out(&clock, &vintage_totals_deltas, &mut tables);
+    db_governance_out(&events, &mut tables);
+    db_activity_windows_out(
+        &clock,
+        &events,
+        &hour_index_deltas,
+        &day_index_deltas,
+        &activity_window_counts,
+        &activity_window_usdc,
+        &mut tables,
+    );
+    db_volume_filled_out(&volume_filled_snapshot_list, &mut tables);
+    Ok(tables.to_database_changes())
+}
+
+#[substreams::handlers::map]
+fn db_clickhouse_out(
+    clock: Clock,
+    events: contract::Events,
+    bid_loan_details_list: teller::BidLoanDetailsList,
+    token_address_delta_store: Deltas<DeltaBigInt>,
+    token_prices: StoreGetFloat64,
+    token_decimals: StoreGetBigInt,
+    pause_window_store: StoreGetString,
+) -> Result<DatabaseChanges, substreams::errors::Error> {
+    // Initialize Database Changes container
+    let mut tables = DatabaseChangeTables::new();
+    db_clickhouse_tellerv2_out(
+        &clock,
+        &events,
+        &bid_loan_details_by_id(&bid_loan_details_list),
+        &token_address_delta_store,
+        &token_prices,
+        &token_decimals,
+        &pause_window_store,
+        &mut tables,
+    );
     Ok(tables.to_database_changes())
 }
-*/
 
 #[substreams::handlers::map]
 fn graph_out(
+    clock: Clock,
     events: contract::Events,
+    bid_loan_details_list: teller::BidLoanDetailsList,
     token_address_delta_store: Deltas<DeltaBigInt>, //each key of the delta array represents a tokenAddress that we need to get price for ..
 
     token_prices: StoreGetFloat64,
     token_decimals: StoreGetBigInt,
+    token_principal: StoreGetBigInt,
+    protocol_principal_usdc: StoreGetFloat64,
+    day_index_deltas: Deltas<DeltaBigInt>,
+    active_bid_buckets: StoreGetBigInt,
+    latest_accepted_bid_id: StoreGetBigInt,
+    active_bid_terms: StoreGetBigInt,
+    borrower_credit_history_deltas: Deltas<DeltaBigInt>,
+    borrower_longest_delinquency_deltas: Deltas<DeltaBigInt>,
+    borrower_borrowed_usdc_deltas: Deltas<DeltaFloat64>,
+    vintage_totals_deltas: Deltas<DeltaBigInt>,
+    pause_window_store: StoreGetString,
+    volume_filled_snapshot_list: teller::VolumeFilledSnapshotList,
+    hour_index_deltas: Deltas<DeltaBigInt>,
+    activity_window_counts: StoreGetBigInt,
+    activity_window_usdc: StoreGetFloat64,
 ) -> Result<EntityChanges, substreams::errors::Error> {
     // Initialize Database Changes container
     let mut tables = EntityChangesTables::new();
     graph_tellerv2_out(
         &events,
+        &bid_loan_details_by_id(&bid_loan_details_list),
+        &token_address_delta_store,
+        &token_prices,
+        &token_decimals,
+        &pause_window_store,
+        &mut tables,
+    );
+    graph_principal_day_data_out(
+        &clock,
         &token_address_delta_store,
+        &token_principal,
+        &protocol_principal_usdc,
         &token_prices,
         &token_decimals,
         &mut tables,
     );
+    graph_interest_accrual_out(
+        &day_index_deltas,
+        &active_bid_buckets,
+        &latest_accepted_bid_id,
+        &active_bid_terms,
+        &mut tables,
+    );
+    graph_borrower_credit_out(
+        &borrower_credit_history_deltas,
+        &borrower_longest_delinquency_deltas,
+        &borrower_borrowed_usdc_deltas,
+        &mut tables,
+    );
+    graph_vintage_out(&clock, &vintage_totals_deltas, &mut tables);
+    graph_governance_out(&events, &mut tables);
+    graph_volume_filled_out(&volume_filled_snapshot_list, &mut tables);
+    graph_activity_windows_out(
+        &clock,
+        &events,
+        &hour_index_deltas,
+        &day_index_deltas,
+        &activity_window_counts,
+        &activity_window_usdc,
+        &mut tables,
+    );
     Ok(tables.to_entity_changes())
 }
 
@@ -1378,6 +5278,126 @@ fn calculate_principal_amount_usdc_internal(
     final_amount
 }
 
+/*
+  Newer TellerV2 versions emit keccak256(metadataURI) in SubmittedBid and only older ones packed the uri itself into the bytes32,
+  so prefer the string passed to submitBid and fall back to the event bytes.
+*/
+fn decode_metadata_uri(
+    submitted_metadata_uri: &str,
+    metadata_uri_bytes: &[u8],
+) -> (Option<String>, &'static str) {
+    let metadata_uri_text = match submitted_metadata_uri.is_empty() {
+        false => Some(submitted_metadata_uri.to_string()),
+        true => {
+            let trimmed_len = metadata_uri_bytes
+                .iter()
+                .rposition(|byte| *byte != 0)
+                .map_or(0, |last_index| last_index + 1);
+
+            match std::str::from_utf8(&metadata_uri_bytes[..trimmed_len]) {
+                Ok(text) if !text.is_empty() && !text.chars().any(|c| c.is_control()) => {
+                    Some(text.to_string())
+                }
+                _ => None,
+            }
+        }
+    };
+
+    let metadata_scheme = match &metadata_uri_text {
+        None if metadata_uri_bytes.iter().all(|byte| *byte == 0) => "empty",
+        None => "bytes32",
+        Some(text) => classify_metadata_uri_scheme(text),
+    };
+
+    (metadata_uri_text, metadata_scheme)
+}
+
+fn classify_metadata_uri_scheme(metadata_uri_text: &str) -> &'static str {
+    let lowercase_uri = metadata_uri_text.to_lowercase();
+
+    if lowercase_uri.starts_with("ipfs://")
+        || lowercase_uri.contains("/ipfs/")
+        || metadata_uri_text.starts_with("Qm")
+        || lowercase_uri.starts_with("bafy")
+    {
+        "ipfs"
+    } else if lowercase_uri.starts_with("ar://") || lowercase_uri.contains("arweave.net/") {
+        "arweave"
+    } else if lowercase_uri.starts_with("https://") {
+        "https"
+    } else if lowercase_uri.starts_with("http://") {
+        "http"
+    } else {
+        "text"
+    }
+}
+
+/*
+  Simple interest as in V2Calculations.calculateAmountOwed:  owedPrincipal.percent(APR) * owedTime / daysInYear
+*/
+fn calculate_accrued_interest(
+    owed_principal: &BigInt,
+    apr_bps: &BigInt,
+    last_repaid_timestamp: i64,
+    timestamp: i64,
+    days_in_year: i64,
+) -> BigInt {
+    if timestamp <= last_repaid_timestamp {
+        return BigInt::zero();
+    }
+
+    let interest_owed_in_a_year = owed_principal.clone() * apr_bps.clone() / BigInt::from(10000);
+    let owed_time = BigInt::from(timestamp - last_repaid_timestamp);
+
+    interest_owed_in_a_year * owed_time / BigInt::from(days_in_year * 86400)
+}
+
+/*
+  Outcome of a closed loan from its longest delinquency, measured per payment against the payment cycle due date.
+  More than bidDefaultDuration late, or liquidated, means it defaulted and was counted then, so there is no outcome left.
+*/
+fn classify_loan_closure(
+    longest_delinquency_seconds: i64,
+    default_duration: i64,
+    liquidated: bool,
+) -> Option<&'static str> {
+    if liquidated || (default_duration > 0 && longest_delinquency_seconds > default_duration) {
+        None
+    } else if longest_delinquency_seconds > 0 {
+        Some("loans_repaid_late")
+    } else {
+        Some("loans_repaid_on_time")
+    }
+}
+
+/*
+  TellerV2 _canLiquidateLoan with no liquidation delay.  A bid without a default duration never defaults.
+*/
+fn is_loan_defaulted_at(timestamp: i64, next_due_date: i64, default_duration: i64) -> bool {
+    default_duration > 0 && next_due_date > 0 && timestamp > next_due_date + default_duration
+}
+
+/*
+  Months since 0000-01 for a unix timestamp, using the days to civil date conversion from http://howardhinnant.github.io/date_algorithms.html
+*/
+fn month_index_from_timestamp(timestamp: i64) -> i64 {
+    let days = timestamp.div_euclid(86400) + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    year * 12 + (month - 1)
+}
+
+fn month_label_from_index(month_index: i64) -> String {
+    format!("{:04}-{:02}", month_index / 12, month_index % 12 + 1)
+}
+
 pub fn address_to_string(address: &Address) -> String {
     format!("0x{:x}", address)
 }
@@ -1587,6 +5607,172 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_decode_metadata_uri_prefers_submitted_string() {
+        let metadata_uri_hash = [171u8; 32];
+
+        let (metadata_uri_text, metadata_scheme) = decode_metadata_uri(
+            "ipfs://QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG",
+            &metadata_uri_hash,
+        );
+
+        assert_eq!(
+            metadata_uri_text,
+            Some("ipfs://QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_string())
+        );
+        assert_eq!(metadata_scheme, "ipfs");
+    }
+
+    #[test]
+    fn test_decode_metadata_uri_from_event_bytes() {
+        let mut packed_uri = [0u8; 32];
+        packed_uri[..17].copy_from_slice(b"https://teller.io");
+
+        let (metadata_uri_text, metadata_scheme) = decode_metadata_uri("", &packed_uri);
+        assert_eq!(metadata_uri_text, Some("https://teller.io".to_string()));
+        assert_eq!(metadata_scheme, "https");
+
+        let (metadata_uri_text, metadata_scheme) = decode_metadata_uri("", &[171u8; 32]);
+        assert_eq!(metadata_uri_text, None);
+        assert_eq!(metadata_scheme, "bytes32");
+
+        let (metadata_uri_text, metadata_scheme) = decode_metadata_uri("", &[0u8; 32]);
+        assert_eq!(metadata_uri_text, None);
+        assert_eq!(metadata_scheme, "empty");
+    }
+
+    #[test]
+    fn test_calculate_accrued_interest() {
+        let owed_principal = BigInt::from_str("1000000000").unwrap(); // 1000 USDC
+        let apr_bps = BigInt::from(1200); // 12%
+
+        // 30 days on a 360 day year is exactly 1%
+        let accrued_interest =
+            calculate_accrued_interest(&owed_principal, &apr_bps, 1_000_000, 1_000_000 + 30 * 86400, 360);
+        assert_eq!(accrued_interest, BigInt::from_str("10000000").unwrap());
+
+        // one day on a 365 day year rounds down like the contract does
+        let accrued_interest =
+            calculate_accrued_interest(&owed_principal, &apr_bps, 1_000_000, 1_000_000 + 86400, 365);
+        assert_eq!(accrued_interest, BigInt::from_str("328767").unwrap());
+
+        let accrued_interest =
+            calculate_accrued_interest(&owed_principal, &apr_bps, 1_000_000, 999_000, 365);
+        assert_eq!(accrued_interest, BigInt::zero());
+    }
+
+    #[test]
+    fn test_classify_loan_closure() {
+        let default_duration = 7 * 86400;
+
+        assert_eq!(
+            classify_loan_closure(0, default_duration, false),
+            Some("loans_repaid_on_time")
+        );
+        assert_eq!(
+            classify_loan_closure(86400, default_duration, false),
+            Some("loans_repaid_late")
+        );
+        assert_eq!(classify_loan_closure(8 * 86400, default_duration, false), None);
+        assert_eq!(classify_loan_closure(86400, default_duration, true), None);
+
+        // without a default duration the loan can only ever be late
+        assert_eq!(classify_loan_closure(30 * 86400, 0, false), Some("loans_repaid_late"));
+    }
+
+    #[test]
+    fn test_is_loan_defaulted_at() {
+        let next_due_date = 1_700_000_000;
+        let default_duration = 7 * 86400;
+
+        assert!(!is_loan_defaulted_at(next_due_date + default_duration, next_due_date, default_duration));
+        assert!(is_loan_defaulted_at(next_due_date + default_duration + 1, next_due_date, default_duration));
+        assert!(!is_loan_defaulted_at(next_due_date + 365 * 86400, next_due_date, 0));
+        assert!(!is_loan_defaulted_at(1_700_000_000, 0, default_duration));
+    }
+
+    #[test]
+    fn test_month_index_from_timestamp() {
+        // 2022-07-10, the TellerV2 mainnet deployment
+        let deployment_month_index = month_index_from_timestamp(1657426010);
+        assert_eq!(month_label_from_index(deployment_month_index), "2022-07");
+
+        // 2024-02-29 23:59:59 and the second after it
+        assert_eq!(month_label_from_index(month_index_from_timestamp(1709251199)), "2024-02");
+        assert_eq!(month_label_from_index(month_index_from_timestamp(1709251200)), "2024-03");
+
+        // 2023-01-01 is six months after the deployment vintage
+        assert_eq!(month_index_from_timestamp(1672531200) - deployment_month_index, 6);
+    }
+
+    fn submit_bid_transaction(input: Vec<u8>, log_block_index: u32) -> eth::TransactionTrace {
+        eth::TransactionTrace {
+            calls: vec![eth::Call {
+                input,
+                logs: vec![eth::Log {
+                    block_index: log_block_index,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_find_submit_bid_call_terms_without_collateral() {
+        let submit_bid = abi::tellerv2_contract::functions::SubmitBid1 {
+            u_lending_token: hex!("a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").to_vec(),
+            u_marketplace_id: BigInt::from(2),
+            u_principal: BigInt::from(1_000_000_000u64),
+            u_duration: BigInt::from(2_592_000),
+            u_apr: BigInt::from(1200),
+            u_metadata_uri: "ipfs://QmTeller".to_string(),
+            u_receiver: hex!("0000000000000000000000000000000000000001").to_vec(),
+        };
+        let transaction = submit_bid_transaction(submit_bid.encode(), 7);
+
+        let terms = find_submit_bid_call_terms(&transaction, 7).unwrap();
+        assert_eq!(terms.apr, 1200);
+        assert_eq!(terms.duration, 2_592_000);
+        assert!(terms.collateral.is_empty());
+        assert_eq!(terms.metadata_uri, "ipfs://QmTeller");
+
+        // a call that did not emit the log is not the submitting call
+        assert!(find_submit_bid_call_terms(&transaction, 8).is_none());
+    }
+
+    #[test]
+    fn test_find_submit_bid_call_terms_with_collateral() {
+        let collateral_address = hex!("c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2").to_vec();
+        let submit_bid = abi::tellerv2_contract::functions::SubmitBid2 {
+            u_lending_token: hex!("a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48").to_vec(),
+            u_marketplace_id: BigInt::from(2),
+            u_principal: BigInt::from(1_000_000_000u64),
+            u_duration: BigInt::from(2_592_000),
+            u_apr: BigInt::from(850),
+            u_metadata_uri: "".to_string(),
+            u_receiver: hex!("0000000000000000000000000000000000000001").to_vec(),
+            u_collateral_info: vec![(
+                BigInt::from(0),
+                BigInt::from_str("500000000000000000").unwrap(),
+                BigInt::from(0),
+                collateral_address.clone(),
+            )],
+        };
+        let transaction = submit_bid_transaction(submit_bid.encode(), 3);
+
+        let terms = find_submit_bid_call_terms(&transaction, 3).unwrap();
+        assert_eq!(terms.apr, 850);
+        assert_eq!(terms.duration, 2_592_000);
+        assert_eq!(terms.metadata_uri, "");
+        assert_eq!(terms.collateral.len(), 1);
+        assert_eq!(terms.collateral[0].collateral_type, 0);
+        assert_eq!(terms.collateral[0].collateral_address, collateral_address);
+        assert_eq!(terms.collateral[0].amount, "500000000000000000");
+        assert_eq!(terms.collateral[0].token_id, "0");
+    }
+
     fn bigint_to_f64(value: &BigInt) -> f64 {
         value.to_string().parse::<f64>().unwrap_or(0.0)
     }
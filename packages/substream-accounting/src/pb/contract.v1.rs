@@ -276,6 +276,26 @@ pub struct Tellerv2SubmittedBid {
     pub receiver: ::prost::alloc::vec::Vec<u8>,
     #[prost(bytes="vec", tag="8")]
     pub metadata_uri: ::prost::alloc::vec::Vec<u8>,
+    #[prost(uint64, optional, tag="9")]
+    pub apr: ::core::option::Option<u64>,
+    #[prost(uint64, optional, tag="10")]
+    pub duration: ::core::option::Option<u64>,
+    #[prost(message, repeated, tag="11")]
+    pub collateral: ::prost::alloc::vec::Vec<Tellerv2BidCollateral>,
+    #[prost(string, tag="12")]
+    pub metadata_uri_text: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Tellerv2BidCollateral {
+    #[prost(uint32, tag="1")]
+    pub collateral_type: u32,
+    #[prost(bytes="vec", tag="2")]
+    pub collateral_address: ::prost::alloc::vec::Vec<u8>,
+    #[prost(string, tag="3")]
+    pub amount: ::prost::alloc::string::String,
+    #[prost(string, tag="4")]
+    pub token_id: ::prost::alloc::string::String,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
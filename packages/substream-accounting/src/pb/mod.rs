@@ -6,3 +6,21 @@ pub mod contract {
         // @@protoc_insertion_point(contract.v1)
     }
 }
+pub mod teller {
+    // @@protoc_insertion_point(attribute:teller.v1)
+    pub mod v1 {
+        include!("teller.v1.rs");
+        // @@protoc_insertion_point(teller.v1)
+    }
+}
+pub mod sf {
+    pub mod substreams {
+        pub mod index {
+            // @@protoc_insertion_point(attribute:sf.substreams.index.v1)
+            pub mod v1 {
+                include!("sf.substreams.index.v1.rs");
+                // @@protoc_insertion_point(sf.substreams.index.v1)
+            }
+        }
+    }
+}
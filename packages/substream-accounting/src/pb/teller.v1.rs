@@ -0,0 +1,224 @@
+// @generated
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BidActivity {
+    #[prost(message, repeated, tag="1")]
+    pub bids: ::prost::alloc::vec::Vec<Bid>,
+    #[prost(message, repeated, tag="2")]
+    pub repayments: ::prost::alloc::vec::Vec<Repayment>,
+    #[prost(message, repeated, tag="3")]
+    pub liquidations: ::prost::alloc::vec::Vec<Liquidation>,
+    #[prost(message, repeated, tag="4")]
+    pub fee_payments: ::prost::alloc::vec::Vec<FeePayment>,
+    #[prost(message, repeated, tag="5")]
+    pub token_prices: ::prost::alloc::vec::Vec<TokenPrice>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Bid {
+    #[prost(string, tag="1")]
+    pub evt_tx_hash: ::prost::alloc::string::String,
+    #[prost(uint32, tag="2")]
+    pub evt_index: u32,
+    #[prost(message, optional, tag="3")]
+    pub evt_block_time: ::core::option::Option<::prost_types::Timestamp>,
+    #[prost(uint64, tag="4")]
+    pub evt_block_number: u64,
+    #[prost(string, tag="5")]
+    pub bid_id: ::prost::alloc::string::String,
+    #[prost(string, tag="6")]
+    pub status: ::prost::alloc::string::String,
+    #[prost(bytes="vec", tag="7")]
+    pub borrower: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes="vec", tag="8")]
+    pub lender: ::prost::alloc::vec::Vec<u8>,
+    #[prost(string, tag="9")]
+    pub market_id: ::prost::alloc::string::String,
+    #[prost(bytes="vec", tag="10")]
+    pub principal_token_address: ::prost::alloc::vec::Vec<u8>,
+    #[prost(string, tag="11")]
+    pub principal_amount: ::prost::alloc::string::String,
+    #[prost(double, tag="12")]
+    pub principal_amount_usdc: f64,
+    #[prost(uint64, tag="13")]
+    pub apr_bps: u64,
+    #[prost(uint64, tag="14")]
+    pub loan_duration: u64,
+    #[prost(uint64, tag="15")]
+    pub accepted_timestamp: u64,
+    #[prost(uint64, tag="16")]
+    pub last_repaid_timestamp: u64,
+    #[prost(string, tag="17")]
+    pub total_repaid_principal: ::prost::alloc::string::String,
+    #[prost(string, tag="18")]
+    pub total_repaid_interest: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Repayment {
+    #[prost(string, tag="1")]
+    pub evt_tx_hash: ::prost::alloc::string::String,
+    #[prost(uint32, tag="2")]
+    pub evt_index: u32,
+    #[prost(message, optional, tag="3")]
+    pub evt_block_time: ::core::option::Option<::prost_types::Timestamp>,
+    #[prost(uint64, tag="4")]
+    pub evt_block_number: u64,
+    #[prost(string, tag="5")]
+    pub bid_id: ::prost::alloc::string::String,
+    #[prost(bool, tag="6")]
+    pub full_repayment: bool,
+    #[prost(bytes="vec", tag="7")]
+    pub principal_token_address: ::prost::alloc::vec::Vec<u8>,
+    #[prost(string, tag="8")]
+    pub principal_repaid: ::prost::alloc::string::String,
+    #[prost(string, tag="9")]
+    pub interest_repaid: ::prost::alloc::string::String,
+    #[prost(double, tag="10")]
+    pub principal_repaid_usdc: f64,
+    #[prost(double, tag="11")]
+    pub interest_repaid_usdc: f64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Liquidation {
+    #[prost(string, tag="1")]
+    pub evt_tx_hash: ::prost::alloc::string::String,
+    #[prost(uint32, tag="2")]
+    pub evt_index: u32,
+    #[prost(message, optional, tag="3")]
+    pub evt_block_time: ::core::option::Option<::prost_types::Timestamp>,
+    #[prost(uint64, tag="4")]
+    pub evt_block_number: u64,
+    #[prost(string, tag="5")]
+    pub bid_id: ::prost::alloc::string::String,
+    #[prost(bytes="vec", tag="6")]
+    pub liquidator: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes="vec", tag="7")]
+    pub principal_token_address: ::prost::alloc::vec::Vec<u8>,
+    #[prost(string, tag="8")]
+    pub principal_liquidated: ::prost::alloc::string::String,
+    #[prost(string, tag="9")]
+    pub interest_liquidated: ::prost::alloc::string::String,
+    #[prost(double, tag="10")]
+    pub principal_liquidated_usdc: f64,
+    #[prost(double, tag="11")]
+    pub interest_liquidated_usdc: f64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FeePayment {
+    #[prost(string, tag="1")]
+    pub evt_tx_hash: ::prost::alloc::string::String,
+    #[prost(uint32, tag="2")]
+    pub evt_index: u32,
+    #[prost(message, optional, tag="3")]
+    pub evt_block_time: ::core::option::Option<::prost_types::Timestamp>,
+    #[prost(uint64, tag="4")]
+    pub evt_block_number: u64,
+    #[prost(string, tag="5")]
+    pub bid_id: ::prost::alloc::string::String,
+    #[prost(string, tag="6")]
+    pub fee_type: ::prost::alloc::string::String,
+    #[prost(bytes="vec", tag="7")]
+    pub principal_token_address: ::prost::alloc::vec::Vec<u8>,
+    #[prost(string, tag="8")]
+    pub amount: ::prost::alloc::string::String,
+    #[prost(double, tag="9")]
+    pub amount_usdc: f64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TokenPrice {
+    #[prost(bytes="vec", tag="1")]
+    pub token_address: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes="vec", tag="2")]
+    pub reference_token_address: ::prost::alloc::vec::Vec<u8>,
+    #[prost(double, tag="3")]
+    pub price_ratio: f64,
+    #[prost(uint64, tag="4")]
+    pub decimals: u64,
+    #[prost(double, tag="5")]
+    pub price_usdc: f64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BidLoanDetailsList {
+    #[prost(message, repeated, tag="1")]
+    pub bids: ::prost::alloc::vec::Vec<BidLoanDetails>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BidLoanDetails {
+    #[prost(string, tag="1")]
+    pub bid_id: ::prost::alloc::string::String,
+    #[prost(bytes="vec", tag="2")]
+    pub borrower: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes="vec", tag="3")]
+    pub lender: ::prost::alloc::vec::Vec<u8>,
+    #[prost(string, tag="4")]
+    pub market_id: ::prost::alloc::string::String,
+    #[prost(bytes="vec", tag="5")]
+    pub principal_token_address: ::prost::alloc::vec::Vec<u8>,
+    #[prost(string, tag="6")]
+    pub principal_amount: ::prost::alloc::string::String,
+    #[prost(string, tag="7")]
+    pub total_repaid_principal: ::prost::alloc::string::String,
+    #[prost(string, tag="8")]
+    pub total_repaid_interest: ::prost::alloc::string::String,
+    #[prost(string, tag="9")]
+    pub accepted_timestamp: ::prost::alloc::string::String,
+    #[prost(string, tag="10")]
+    pub last_repaid_timestamp: ::prost::alloc::string::String,
+    #[prost(string, tag="11")]
+    pub loan_duration: ::prost::alloc::string::String,
+    #[prost(string, tag="12")]
+    pub payment_cycle_amount: ::prost::alloc::string::String,
+    #[prost(string, tag="13")]
+    pub payment_cycle: ::prost::alloc::string::String,
+    #[prost(string, tag="14")]
+    pub apr: ::prost::alloc::string::String,
+    #[prost(string, tag="15")]
+    pub bid_state: ::prost::alloc::string::String,
+    #[prost(string, tag="16")]
+    pub payment_type: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VolumeFilledSnapshotList {
+    #[prost(message, repeated, tag="1")]
+    pub snapshots: ::prost::alloc::vec::Vec<VolumeFilledSnapshot>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VolumeFilledSnapshot {
+    #[prost(string, tag="1")]
+    pub evt_tx_hash: ::prost::alloc::string::String,
+    #[prost(uint32, tag="2")]
+    pub evt_index: u32,
+    #[prost(message, optional, tag="3")]
+    pub evt_block_time: ::core::option::Option<::prost_types::Timestamp>,
+    #[prost(uint64, tag="4")]
+    pub evt_block_number: u64,
+    #[prost(string, tag="5")]
+    pub bid_id: ::prost::alloc::string::String,
+    #[prost(bytes="vec", tag="6")]
+    pub lender: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes="vec", tag="7")]
+    pub principal_token_address: ::prost::alloc::vec::Vec<u8>,
+    #[prost(string, optional, tag="8")]
+    pub lender_volume_filled: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag="9")]
+    pub total_volume_filled: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag="10")]
+    pub legacy_lender_volume_filled: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag="11")]
+    pub legacy_total_volume_filled: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, tag="12")]
+    pub indexed_lender_volume_filled: ::prost::alloc::string::String,
+    #[prost(string, tag="13")]
+    pub indexed_total_volume_filled: ::prost::alloc::string::String,
+    #[prost(bool, tag="14")]
+    pub volume_mismatch: bool,
+}
+// @@protoc_insertion_point(module)
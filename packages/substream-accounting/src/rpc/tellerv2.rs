@@ -6,7 +6,6 @@ use prost::Message;
 use hex_literal::hex;
 use substreams::log;
 use substreams::scalar::BigInt;
-use substreams::Hex;
 use substreams_ethereum::rpc::RpcBatch;
 
 /*
@@ -20,20 +19,15 @@ https://github.com/streamingfast/substreams-uniswap-v3/blob/develop/src/rpc.rs
 
 /*
 
-[ getLoanSummary(uint256) method Response ]
-  borrower   address :  0x8EC0bcFC6Fca34c5dAD0680306f0242EA87E7703
-  lender   address :  0x62C04179D85f2D776A028a1453F2Ded314b18BC8
-  marketId   uint256 :  1
-  principalTokenAddress   address :  0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2
-  principalAmount   uint256 :  8362295945234333859
-  acceptedTimestamp   uint32 :  1657426010
-  lastRepaidTimestamp   uint32 :  1664926967
-  bidState   uint8 :  4
-
-
+[ bids(uint256) method Response ]
+  borrower, receiver, lender, marketplaceId, _metadataURI,
+  loanDetails (lendingToken, principal, totalRepaid (principal, interest), timestamp, acceptedTimestamp, lastRepaidTimestamp, loanDuration),
+  terms (paymentCycleAmount, paymentCycle, APR),
+  state, paymentType
 
+  Unlike getLoanSummary this has existed since the first deployment, and it also carries the repaid totals and terms.
 */
-pub struct LoanSummaryData {
+pub struct BidLoanDetailsData {
     pub bid_id: BigInt,
 
     pub borrower_address: Address,
@@ -46,60 +40,183 @@ pub struct LoanSummaryData {
 
     pub principal_amount: BigInt,
 
+    pub total_repaid_principal: BigInt,
+
+    pub total_repaid_interest: BigInt,
+
     pub accepted_timestamp: BigInt,
 
     pub last_repaid_timestamp: BigInt,
 
-    pub bid_state: BigInt,
-}
+    pub loan_duration: BigInt,
 
-const TELLERV2_TRACKED_CONTRACT: &str = "00182FdB0B880eE24D428e3Cc39383717677C37e";
+    pub payment_cycle_amount: BigInt,
 
-/*
+    pub payment_cycle: BigInt,
 
-This needs to be optional!!  The getLoanSummary method has not always existed on the contract.
-*/
-pub fn fetch_loan_summary_from_rpc(
-    teller_v2_address: &Address,
-    bid_id: &BigInt,
-) -> Option<LoanSummaryData> {
-    //let  contract_address = Hex(& TELLERV2_TRACKED_CONTRACT ).to_string();
+    pub apr: BigInt,
+
+    pub bid_state: BigInt,
 
-    //   let bid_id = BigInt::from( 0 );
+    pub payment_type: BigInt,
+}
 
-    let teller_v2_address_decoded = Hex::decode(TELLERV2_TRACKED_CONTRACT).unwrap();
+pub const BID_STATE_ACCEPTED: u64 = 3;
+pub const BID_STATE_PAID: u64 = 4;
+pub const BID_STATE_LIQUIDATED: u64 = 5;
 
-    let loan_summary_function = abi::tellerv2_contract::functions::GetLoanSummary {
-        u_bid_id: bid_id.clone(),
+pub fn fetch_bid_loan_details_from_rpc(
+    teller_v2_address: &Address,
+    bid_id: &BigInt,
+) -> Option<BidLoanDetailsData> {
+    let bids_function = abi::tellerv2_contract::functions::Bids {
+        param0: bid_id.clone(),
     };
 
     if let Some((
         borrower_address,
+        _receiver_address,
         lender_address,
         market_id,
-        principal_token_address,
-        principal_amount,
-        accepted_timestamp,
-        last_repaid_timestamp,
+        _metadata_uri,
+        (
+            principal_token_address,
+            principal_amount,
+            (total_repaid_principal, total_repaid_interest),
+            _submitted_timestamp,
+            accepted_timestamp,
+            last_repaid_timestamp,
+            loan_duration,
+        ),
+        (payment_cycle_amount, payment_cycle, apr),
         bid_state,
-    )) = loan_summary_function.call(teller_v2_address_decoded.clone())
+        payment_type,
+    )) = bids_function.call(teller_v2_address.as_bytes().to_vec())
     {
-        return Some(LoanSummaryData {
+        return Some(BidLoanDetailsData {
             bid_id: bid_id.clone(),
             borrower_address: H160::from_slice(&borrower_address),
             lender_address: H160::from_slice(&lender_address),
             market_id,
             principal_token_address: H160::from_slice(&principal_token_address),
             principal_amount,
+            total_repaid_principal,
+            total_repaid_interest,
             accepted_timestamp,
             last_repaid_timestamp,
+            loan_duration,
+            payment_cycle_amount,
+            payment_cycle,
+            apr,
             bid_state,
+            payment_type,
         });
     }
 
     None
 }
 
+/*
+ bidExpirationTime is stored as a window in seconds, measured from the bid submission timestamp.
+*/
+pub fn fetch_bid_expiration_time_from_rpc(
+    teller_v2_address: &Address,
+    bid_id: &BigInt,
+) -> Option<BigInt> {
+    let bid_expiration_time_function = abi::tellerv2_contract::functions::BidExpirationTime {
+        param0: bid_id.clone(),
+    };
+
+    bid_expiration_time_function.call(teller_v2_address.as_bytes().to_vec())
+}
+
+/*
+ bidPaymentCycleType was added after launch, bids without it accrue over a 365 day year.
+ Monthly (1) cycles use a 360 day year in V2Calculations.
+*/
+pub fn fetch_bid_payment_cycle_type_from_rpc(
+    teller_v2_address: &Address,
+    bid_id: &BigInt,
+) -> Option<BigInt> {
+    let bid_payment_cycle_type_function = abi::tellerv2_contract::functions::BidPaymentCycleType {
+        param0: bid_id.clone(),
+    };
+
+    bid_payment_cycle_type_function.call(teller_v2_address.as_bytes().to_vec())
+}
+
+/*
+ bidDefaultDuration is the grace period in seconds after a due date before the loan counts as defaulted.
+*/
+pub fn fetch_bid_default_duration_from_rpc(
+    teller_v2_address: &Address,
+    bid_id: &BigInt,
+) -> Option<BigInt> {
+    let bid_default_duration_function = abi::tellerv2_contract::functions::BidDefaultDuration {
+        param0: bid_id.clone(),
+    };
+
+    bid_default_duration_function.call(teller_v2_address.as_bytes().to_vec())
+}
+
+/*
+ calculateNextDueDate is the due date of the payment cycle the loan is currently in, it moves forward with every repayment.
+ Zero once the bid is no longer accepted.
+*/
+pub fn fetch_bid_next_due_date_from_rpc(
+    teller_v2_address: &Address,
+    bid_id: &BigInt,
+) -> Option<BigInt> {
+    let calculate_next_due_date_function = abi::tellerv2_contract::functions::CalculateNextDueDate {
+        u_bid_id: bid_id.clone(),
+    };
+
+    calculate_next_due_date_function.call(teller_v2_address.as_bytes().to_vec())
+}
+
+/*
+ Volume counters kept by the contract.  lenderVolumeFilled / totalVolumeFilled are per lending token,
+ the u-prefixed ones are the deprecated __lenderVolumeFilled / __totalVolumeFilled from before that split.
+*/
+pub struct VolumeFilledData {
+    pub lender_volume_filled: Option<BigInt>,
+
+    pub total_volume_filled: Option<BigInt>,
+
+    pub legacy_lender_volume_filled: Option<BigInt>,
+
+    pub legacy_total_volume_filled: Option<BigInt>,
+}
+
+pub fn fetch_volume_filled_from_rpc(
+    teller_v2_address: &Address,
+    lending_token_address: &Address,
+    lender_address: &Address,
+) -> VolumeFilledData {
+    let teller_v2_address_bytes = teller_v2_address.as_bytes().to_vec();
+
+    VolumeFilledData {
+        lender_volume_filled: abi::tellerv2_contract::functions::LenderVolumeFilled {
+            param0: lending_token_address.as_bytes().to_vec(),
+            param1: lender_address.as_bytes().to_vec(),
+        }
+        .call(teller_v2_address_bytes.clone()),
+
+        total_volume_filled: abi::tellerv2_contract::functions::TotalVolumeFilled {
+            param0: lending_token_address.as_bytes().to_vec(),
+        }
+        .call(teller_v2_address_bytes.clone()),
+
+        legacy_lender_volume_filled: abi::tellerv2_contract::functions::ULenderVolumeFilled {
+            param0: lender_address.as_bytes().to_vec(),
+        }
+        .call(teller_v2_address_bytes.clone()),
+
+        legacy_total_volume_filled: abi::tellerv2_contract::functions::UTotalVolumeFilled {}
+            .call(teller_v2_address_bytes),
+    }
+}
+
 /*let Some((
     borrower_address,
     lender_address,